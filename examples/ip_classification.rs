@@ -1,5 +1,7 @@
 /* examples/ip_classification.rs */
 
+//! Classify a handful of IP addresses and react to their category.
+
 use netsem::{IpClass, classify_ip, parse_ip};
 
 fn main() {
@@ -33,6 +35,9 @@ fn main() {
 					IpClass::Unspecified => println!("  -> This is an unspecified address."),
 					IpClass::SharedAddress => println!("  -> This is a shared/CGNAT address."),
 					IpClass::Benchmarking => println!("  -> This is a benchmarking address."),
+					IpClass::ProtocolAssignment => {
+						println!("  -> This is an IETF protocol assignment address.")
+					}
 					_ => println!("  -> Unknown classification."),
 				}
 			}
@@ -1,5 +1,7 @@
 /* examples/port_ranges.rs */
 
+//! Classify ports into their IANA System, User and Dynamic ranges.
+
 use netsem::{PortClass, classify_port};
 
 fn main() {
@@ -1,5 +1,7 @@
 /* examples/simple.rs */
 
+//! Parse a socket address, validate its port and optionally check it can be bound.
+
 use netsem::{validate_port, validate_socket_addr};
 
 #[cfg(feature = "check")]
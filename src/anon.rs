@@ -0,0 +1,333 @@
+/* src/anon.rs */
+
+use crate::ip::classify_ip;
+use crate::net::IpNet;
+use core::fmt;
+use core::net::IpAddr;
+
+#[cfg(feature = "anon")]
+use crate::ip::{bits_to_ip, class_prefixes, ip_to_bits};
+#[cfg(feature = "anon")]
+use hmac::{Hmac, Mac};
+#[cfg(feature = "anon")]
+use sha2::Sha256;
+
+/// How many leading bits to keep when truncating addresses.
+///
+/// The default keeps an IPv4 `/24` and an IPv6 `/48`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Truncation {
+	/// Prefix length kept for IPv4 addresses (0-32).
+	pub ipv4_prefix: u8,
+	/// Prefix length kept for IPv6 addresses (0-128).
+	pub ipv6_prefix: u8,
+}
+
+impl Default for Truncation {
+	fn default() -> Self {
+		Self {
+			ipv4_prefix: 24,
+			ipv6_prefix: 48,
+		}
+	}
+}
+
+impl Truncation {
+	/// Returns the prefix covering `ip` at the configured length.
+	///
+	/// Lengths beyond the family's width keep the whole address.
+	#[must_use]
+	pub fn prefix(&self, ip: IpAddr) -> IpNet {
+		let (len, max) = if ip.is_ipv4() {
+			(self.ipv4_prefix, 32)
+		} else {
+			(self.ipv6_prefix, 128)
+		};
+		IpNet::new(ip, len.min(max)).expect("prefix length is clamped")
+	}
+}
+
+/// Zeroes the host bits of `ip` beyond the configured prefix length.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::anon::{Truncation, truncate};
+///
+/// let ip = truncate("203.0.113.77".parse().unwrap(), Truncation::default());
+/// assert_eq!(ip.to_string(), "203.0.113.0");
+///
+/// let ip = truncate("2001:db8:1:2::5".parse().unwrap(), Truncation::default());
+/// assert_eq!(ip.to_string(), "2001:db8:1::");
+/// ```
+#[must_use]
+pub fn truncate(ip: IpAddr, truncation: Truncation) -> IpAddr {
+	truncation.prefix(ip).network()
+}
+
+/// Formats an address with only its family and [`classify_ip`] class.
+///
+/// Both `Display` and `Debug` redact, so the wrapper is safe to pass to any
+/// logging macro.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::anon::Redacted;
+///
+/// let ip = "192.168.1.20".parse().unwrap();
+/// assert_eq!(Redacted(ip).to_string(), "<redacted ipv4 private>");
+/// assert_eq!(format!("{:?}", Redacted(ip)), "<redacted ipv4 private>");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Redacted(pub IpAddr);
+
+impl fmt::Display for Redacted {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let family = if self.0.is_ipv4() { "ipv4" } else { "ipv6" };
+		write!(f, "<redacted {family} {}>", classify_ip(self.0))
+	}
+}
+
+impl fmt::Debug for Redacted {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(self, f)
+	}
+}
+
+/// Formats an address as its truncated prefix (e.g., `203.0.113.0/24`).
+///
+/// Both `Display` and `Debug` truncate, so subnet-level analytics keep working
+/// while individual hosts stay hidden.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::anon::{Truncated, Truncation};
+///
+/// let ip = "203.0.113.77".parse().unwrap();
+/// assert_eq!(Truncated(ip, Truncation::default()).to_string(), "203.0.113.0/24");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Truncated(pub IpAddr, pub Truncation);
+
+impl fmt::Display for Truncated {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.1.prefix(self.0), f)
+	}
+}
+
+impl fmt::Debug for Truncated {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(self, f)
+	}
+}
+
+/// Keyed pseudonymization of IP addresses.
+///
+/// Both mappings are deterministic for a given key, so the same client maps
+/// to the same pseudonym across log files, and neither can be reversed
+/// without the key.
+#[cfg(feature = "anon")]
+#[derive(Clone)]
+pub struct Anonymizer {
+	mac: Hmac<Sha256>,
+}
+
+#[cfg(feature = "anon")]
+impl fmt::Debug for Anonymizer {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Anonymizer").finish_non_exhaustive()
+	}
+}
+
+#[cfg(feature = "anon")]
+impl Anonymizer {
+	/// Creates an anonymizer from a secret key (any length; 32 random bytes recommended).
+	#[must_use]
+	pub fn new(key: &[u8]) -> Self {
+		Self {
+			mac: Hmac::new_from_slice(key).expect("HMAC accepts keys of any length"),
+		}
+	}
+
+	/// Keyed PRF over a domain tag and message.
+	fn prf(&self, tag: u8, msg: &[u8]) -> [u8; 32] {
+		let mut mac = self.mac.clone();
+		mac.update(&[tag]);
+		mac.update(msg);
+		mac.finalize().into_bytes().into()
+	}
+
+	/// Replaces `ip` with a keyed hash that [`classify_ip`] classifies the same way.
+	///
+	/// The bits of the special-purpose prefix containing `ip` (e.g., `10.0.0.0/8`
+	/// for a private address) are kept and the remaining bits are replaced by
+	/// keyed hash output. Global addresses are re-hashed until the result is
+	/// global again.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::anon::Anonymizer;
+	/// use netsem::{IpClass, classify_ip};
+	///
+	/// let anon = Anonymizer::new(b"0123456789abcdef0123456789abcdef");
+	/// let ip = "10.20.30.40".parse().unwrap();
+	/// let pseudonym = anon.hash_preserving_class(ip);
+	/// assert_ne!(pseudonym, ip);
+	/// assert_eq!(classify_ip(pseudonym), IpClass::Private);
+	/// assert_eq!(anon.hash_preserving_class(ip), pseudonym);
+	/// ```
+	#[must_use]
+	pub fn hash_preserving_class(&self, ip: IpAddr) -> IpAddr {
+		let class = classify_ip(ip);
+		let keep = class_prefixes(class)
+			.find(|net| net.contains(ip))
+			.unwrap_or_else(|| IpNet::new(ip, 0).expect("/0 is always valid"));
+		let base = ip_to_bits(keep.network());
+		let host = ip_to_bits(keep.hostmask());
+		let mut msg = [0u8; 20];
+		msg[..16].copy_from_slice(&ip_to_bits(ip).to_be_bytes());
+		for counter in 0u32.. {
+			msg[16..].copy_from_slice(&counter.to_be_bytes());
+			let digest = self.prf(0x01, &msg);
+			let hashed = u128::from_be_bytes(digest[..16].try_into().expect("digest is 32 bytes"));
+			let candidate = bits_to_ip(base | (hashed & host), ip.is_ipv4());
+			if classify_ip(candidate) == class {
+				return candidate;
+			}
+		}
+		unreachable!("a global address is found long before the counter wraps")
+	}
+
+	/// Crypto-PAn-style prefix-preserving anonymization.
+	///
+	/// Two addresses sharing their first `k` bits map to pseudonyms that also
+	/// share exactly their first `k` bits, so subnet structure survives while
+	/// the actual prefixes are hidden. Bit `i` of the output is bit `i` of the
+	/// input XOR a keyed PRF of the first `i` input bits (HMAC-SHA-256 stands in
+	/// for the AES of the original scheme). Classes are not preserved.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::anon::Anonymizer;
+	/// use netsem::IpNet;
+	///
+	/// let anon = Anonymizer::new(b"0123456789abcdef0123456789abcdef");
+	/// let a = anon.prefix_preserving("192.0.2.10".parse().unwrap());
+	/// let b = anon.prefix_preserving("192.0.2.200".parse().unwrap());
+	/// assert_eq!(IpNet::new(a, 24).unwrap(), IpNet::new(b, 24).unwrap());
+	/// ```
+	#[must_use]
+	pub fn prefix_preserving(&self, ip: IpAddr) -> IpAddr {
+		let width: u32 = if ip.is_ipv4() { 32 } else { 128 };
+		let bits = ip_to_bits(ip);
+		let mut flips = 0u128;
+		for i in 0..width {
+			// Message: family width, prefix length and the first `i` input bits.
+			let prefix = if i == 0 { 0 } else { bits >> (width - i) };
+			let mut msg = [0u8; 18];
+			msg[0] = width as u8;
+			msg[1] = i as u8;
+			msg[2..].copy_from_slice(&prefix.to_be_bytes());
+			if self.prf(0x02, &msg)[0] & 0x80 != 0 {
+				flips |= 1 << (width - 1 - i);
+			}
+		}
+		bits_to_ip(bits ^ flips, ip.is_ipv4())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ip(s: &str) -> IpAddr {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn test_truncate() {
+		let t = Truncation {
+			ipv4_prefix: 16,
+			ipv6_prefix: 64,
+		};
+		assert_eq!(truncate(ip("10.20.30.40"), t), ip("10.20.0.0"));
+		assert_eq!(truncate(ip("2001:db8:1:2:3::4"), t), ip("2001:db8:1:2::"));
+		let keep_all = Truncation {
+			ipv4_prefix: 40,
+			ipv6_prefix: 200,
+		};
+		assert_eq!(truncate(ip("10.20.30.40"), keep_all), ip("10.20.30.40"));
+	}
+
+	#[test]
+	fn test_wrappers_redact() {
+		let addr = ip("2001:db8::1");
+		let shown = format!("{} {:?}", Redacted(addr), Redacted(addr));
+		assert!(!shown.contains("2001"));
+		assert_eq!(Redacted(addr).to_string(), "<redacted ipv6 documentation>");
+		let t = Truncated(addr, Truncation::default());
+		assert_eq!(format!("{t} {t:?}"), "2001:db8::/48 2001:db8::/48");
+	}
+
+	#[test]
+	#[cfg(feature = "anon")]
+	fn test_hash_preserving_class() {
+		let anon = Anonymizer::new(b"test key");
+		let other = Anonymizer::new(b"other key");
+		for s in [
+			"8.8.8.8",
+			"10.1.2.3",
+			"172.16.5.4",
+			"100.64.1.1",
+			"169.254.9.9",
+			"127.0.0.1",
+			"2606:4700::1111",
+			"fd12:3456::1",
+			"fe80::1",
+		] {
+			let addr = ip(s);
+			let hashed = anon.hash_preserving_class(addr);
+			assert_eq!(classify_ip(hashed), classify_ip(addr), "{s}");
+			assert_eq!(hashed.is_ipv4(), addr.is_ipv4());
+			assert_eq!(anon.hash_preserving_class(addr), hashed);
+			assert_ne!(other.hash_preserving_class(addr), hashed, "{s}");
+		}
+		// Single-address classes have nothing left to hash.
+		let broadcast = ip("255.255.255.255");
+		assert_eq!(anon.hash_preserving_class(broadcast), broadcast);
+		assert_eq!(
+			classify_ip(anon.hash_preserving_class(ip("1.1.1.1"))),
+			crate::IpClass::Global
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "anon")]
+	fn test_prefix_preserving() {
+		let anon = Anonymizer::new(b"test key");
+		let common = |a: IpAddr, b: IpAddr| (ip_to_bits(a) ^ ip_to_bits(b)).leading_zeros();
+
+		let pairs = [
+			("10.0.0.1", "10.0.0.2"),
+			("10.0.0.1", "10.0.1.1"),
+			("10.0.0.1", "192.168.0.1"),
+			("2001:db8::1", "2001:db8:0:1::1"),
+			("2001:db8::1", "2a00::1"),
+		];
+		for (a, b) in pairs {
+			let (a, b) = (ip(a), ip(b));
+			let (pa, pb) = (anon.prefix_preserving(a), anon.prefix_preserving(b));
+			assert_eq!(common(pa, pb), common(a, b), "{a} {b}");
+			assert_eq!(pa.is_ipv4(), a.is_ipv4());
+		}
+		assert_eq!(
+			anon.prefix_preserving(ip("10.0.0.1")),
+			anon.prefix_preserving(ip("10.0.0.1"))
+		);
+		assert_ne!(anon.prefix_preserving(ip("10.0.0.1")), ip("10.0.0.1"));
+	}
+}
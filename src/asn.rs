@@ -0,0 +1,372 @@
+/* src/asn.rs */
+
+use crate::error::NetSemError;
+use alloc::borrow::ToOwned;
+use core::fmt;
+use core::ops::RangeInclusive;
+use core::str::FromStr;
+
+/// An Autonomous System Number (32-bit, RFC 6793).
+///
+/// Displays as `AS65001`. Parsing accepts asplain (`"65001"`), an optional
+/// case-insensitive `AS` prefix, and asdot notation (`"1.10"` for 65546,
+/// RFC 5396).
+///
+/// # Examples
+///
+/// ```
+/// use netsem::Asn;
+///
+/// let asn: Asn = "AS65001".parse().unwrap();
+/// assert_eq!(asn.get(), 65001);
+/// assert_eq!("1.10".parse::<Asn>().unwrap().get(), 65546);
+/// assert_eq!(Asn::new(65546).to_string(), "AS65546");
+/// assert_eq!(Asn::new(65546).asdot().to_string(), "1.10");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Asn(u32);
+
+impl Asn {
+	/// `AS_TRANS`, the 16-bit placeholder for 32-bit ASNs (RFC 6793).
+	pub const AS_TRANS: Self = Self(23456);
+
+	/// Creates an ASN from its numeric value.
+	#[must_use]
+	pub const fn new(asn: u32) -> Self {
+		Self(asn)
+	}
+
+	/// Returns the numeric value.
+	#[must_use]
+	pub const fn get(self) -> u32 {
+		self.0
+	}
+
+	/// Returns `true` if the ASN fits in 16 bits (0-65535).
+	#[must_use]
+	pub const fn is_16bit(self) -> bool {
+		self.0 <= 0xffff
+	}
+
+	/// Formats the ASN in asdot notation: `high.low` above 65535, plain otherwise.
+	#[must_use]
+	pub fn asdot(self) -> impl fmt::Display {
+		AsDot(self.0)
+	}
+}
+
+struct AsDot(u32);
+
+impl fmt::Display for AsDot {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.0 > 0xffff {
+			write!(f, "{}.{}", self.0 >> 16, self.0 & 0xffff)
+		} else {
+			write!(f, "{}", self.0)
+		}
+	}
+}
+
+impl From<u32> for Asn {
+	fn from(asn: u32) -> Self {
+		Self(asn)
+	}
+}
+
+impl From<Asn> for u32 {
+	fn from(asn: Asn) -> Self {
+		asn.0
+	}
+}
+
+impl fmt::Display for Asn {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "AS{}", self.0)
+	}
+}
+
+impl FromStr for Asn {
+	type Err = NetSemError;
+
+	/// Parses `65001`, `AS65001`, `as65001`, `1.10` or `AS1.10`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || NetSemError::InvalidAsn(s.to_owned());
+		let digits = match s.get(..2) {
+			Some(prefix) if prefix.eq_ignore_ascii_case("as") => &s[2..],
+			_ => s,
+		};
+		let number = |part: &str| {
+			if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+				return Err(invalid());
+			}
+			part.parse::<u32>().map_err(|_| invalid())
+		};
+		if let Some((high, low)) = digits.split_once('.') {
+			let (high, low) = (number(high)?, number(low)?);
+			if high > 0xffff || low > 0xffff {
+				return Err(invalid());
+			}
+			return Ok(Self((high << 16) | low));
+		}
+		number(digits).map(Self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Asn {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Asn {
+	/// Accepts any string form [`Asn`] parses, or a bare integer.
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct AsnVisitor;
+
+		impl serde::de::Visitor<'_> for AsnVisitor {
+			type Value = Asn;
+
+			fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				f.write_str("an AS number such as \"AS65001\", \"1.10\" or 65001")
+			}
+
+			fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Asn, E> {
+				u32::try_from(v)
+					.map(Asn)
+					.map_err(|_| E::custom(NetSemError::InvalidAsn(alloc::format!("{v}"))))
+			}
+
+			fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Asn, E> {
+				v.parse().map_err(E::custom)
+			}
+		}
+
+		deserializer.deserialize_any(AsnVisitor)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Asn {
+	fn schema_name() -> alloc::borrow::Cow<'static, str> {
+		"Asn".into()
+	}
+
+	fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		schemars::json_schema!({
+			"type": ["string", "integer"],
+			"description": "Autonomous System Number, e.g. \"AS65001\", \"1.10\" or 65001."
+		})
+	}
+}
+
+/// Classification of an Autonomous System Number.
+///
+/// The string form (used by `Display`, `FromStr` and serde) is the lowercase
+/// variant name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum AsnClass {
+	/// Private use (64512-65534, 4200000000-4294967294; RFC 6996).
+	Private,
+	/// Documentation (64496-64511, 65536-65551; RFC 5398).
+	Documentation,
+	/// Reserved (0, AS_TRANS 23456, 65535, 65552-131071, 4294967295).
+	Reserved,
+	/// Any other, publicly assignable ASN.
+	Public,
+}
+
+impl AsnClass {
+	/// Every class, in declaration order.
+	pub const ALL: &'static [Self] = &[
+		Self::Private,
+		Self::Documentation,
+		Self::Reserved,
+		Self::Public,
+	];
+
+	/// Returns the stable lowercase name of the class.
+	#[must_use]
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Private => "private",
+			Self::Documentation => "documentation",
+			Self::Reserved => "reserved",
+			Self::Public => "public",
+		}
+	}
+}
+
+impl fmt::Display for AsnClass {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for AsnClass {
+	type Err = NetSemError;
+
+	/// Parses the name produced by [`AsnClass::as_str`].
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"private" => Ok(Self::Private),
+			"documentation" => Ok(Self::Documentation),
+			"reserved" => Ok(Self::Reserved),
+			"public" => Ok(Self::Public),
+			_ => Err(NetSemError::UnknownClass(s.to_owned())),
+		}
+	}
+}
+
+/// Special-purpose ASN ranges, per the IANA special-purpose AS number registry.
+const CLASS_RANGES: &[(AsnClass, RangeInclusive<u32>)] = &[
+	(AsnClass::Reserved, 0..=0),
+	(AsnClass::Reserved, 23456..=23456),
+	(AsnClass::Documentation, 64496..=64511),
+	(AsnClass::Private, 64512..=65534),
+	(AsnClass::Reserved, 65535..=65535),
+	(AsnClass::Documentation, 65536..=65551),
+	(AsnClass::Reserved, 65552..=131_071),
+	(AsnClass::Private, 4_200_000_000..=4_294_967_294),
+	(AsnClass::Reserved, 4_294_967_295..=4_294_967_295),
+];
+
+/// Classifies an Autonomous System Number.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{Asn, AsnClass, classify_asn};
+///
+/// assert_eq!(classify_asn("AS65001".parse().unwrap()), AsnClass::Private);
+/// assert_eq!(classify_asn(Asn::new(64500)), AsnClass::Documentation);
+/// assert_eq!(classify_asn(Asn::AS_TRANS), AsnClass::Reserved);
+/// assert_eq!(classify_asn(Asn::new(15169)), AsnClass::Public);
+/// ```
+#[must_use]
+pub fn classify_asn(asn: Asn) -> AsnClass {
+	CLASS_RANGES
+		.iter()
+		.find(|(_, range)| range.contains(&asn.0))
+		.map_or(AsnClass::Public, |(class, _)| *class)
+}
+
+/// Returns the ASN ranges [`classify_asn`] maps to `class`.
+///
+/// `AsnClass::Public` has no fixed ranges: it is everything else.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{AsnClass, asn_class_ranges};
+///
+/// let private: Vec<_> = asn_class_ranges(AsnClass::Private).collect();
+/// assert_eq!(private, [64512..=65534, 4_200_000_000..=4_294_967_294]);
+/// ```
+pub fn asn_class_ranges(class: AsnClass) -> impl Iterator<Item = RangeInclusive<u32>> {
+	CLASS_RANGES
+		.iter()
+		.filter(move |(c, _)| *c == class)
+		.map(|(_, range)| range.clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_asn() {
+		for (s, n) in [
+			("0", 0),
+			("65001", 65001),
+			("AS65001", 65001),
+			("as65001", 65001),
+			("As4294967295", u32::MAX),
+			("1.10", 65546),
+			("AS65535.65535", u32::MAX),
+			("0.65001", 65001),
+		] {
+			assert_eq!(s.parse::<Asn>().unwrap().get(), n, "{s}");
+		}
+		for bad in [
+			"",
+			"AS",
+			"AS-1",
+			"+5",
+			"4294967296",
+			"65536.0",
+			"1.65536",
+			"1.",
+			".1",
+			"1.2.3",
+			" 1",
+			"ASN1",
+			"AS 1",
+		] {
+			assert!(bad.parse::<Asn>().is_err(), "{bad:?}");
+		}
+	}
+
+	#[test]
+	fn test_display_roundtrip() {
+		for n in [0, 1, 65535, 65536, 4_200_000_000, u32::MAX] {
+			let asn = Asn::new(n);
+			assert_eq!(asn.to_string().parse::<Asn>().unwrap(), asn);
+			assert_eq!(asn.asdot().to_string().parse::<Asn>().unwrap(), asn);
+		}
+		assert_eq!(Asn::new(65535).asdot().to_string(), "65535");
+		assert_eq!(Asn::new(u32::MAX).asdot().to_string(), "65535.65535");
+	}
+
+	#[test]
+	fn test_classify_asn() {
+		for (n, class) in [
+			(0, AsnClass::Reserved),
+			(1, AsnClass::Public),
+			(23456, AsnClass::Reserved),
+			(64495, AsnClass::Public),
+			(64496, AsnClass::Documentation),
+			(64511, AsnClass::Documentation),
+			(64512, AsnClass::Private),
+			(65534, AsnClass::Private),
+			(65535, AsnClass::Reserved),
+			(65536, AsnClass::Documentation),
+			(65551, AsnClass::Documentation),
+			(65552, AsnClass::Reserved),
+			(131_072, AsnClass::Public),
+			(4_199_999_999, AsnClass::Public),
+			(4_200_000_000, AsnClass::Private),
+			(4_294_967_294, AsnClass::Private),
+			(4_294_967_295, AsnClass::Reserved),
+		] {
+			assert_eq!(classify_asn(Asn::new(n)), class, "{n}");
+		}
+		for &class in AsnClass::ALL {
+			assert_eq!(class.as_str().parse::<AsnClass>().unwrap(), class);
+			for range in asn_class_ranges(class) {
+				assert_eq!(classify_asn(Asn::new(*range.start())), class);
+				assert_eq!(classify_asn(Asn::new(*range.end())), class);
+			}
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn test_asn_serde() {
+		let asn: Asn = serde_json::from_str("65001").unwrap();
+		assert_eq!(serde_json::to_string(&asn).unwrap(), r#""AS65001""#);
+		let asn: Asn = serde_json::from_str(r#""1.10""#).unwrap();
+		assert_eq!(asn.get(), 65546);
+		assert!(serde_json::from_str::<Asn>("4294967296").is_err());
+		assert!(serde_json::from_str::<Asn>(r#""AS-1""#).is_err());
+		let class: AsnClass = serde_json::from_str(r#""documentation""#).unwrap();
+		assert_eq!(class, AsnClass::Documentation);
+	}
+}
@@ -0,0 +1,406 @@
+/* src/cloud.rs */
+
+use crate::error::NetSemError;
+use crate::net::{IpNet, PrefixTable};
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+/// A cloud provider publishing an IP range feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum CloudProvider {
+	/// Amazon Web Services (`ip-ranges.json`).
+	Aws,
+	/// Google Cloud (`cloud.json`).
+	Gcp,
+	/// Microsoft Azure (`ServiceTags_*.json`).
+	Azure,
+	/// Cloudflare (`ips-v4` / `ips-v6` text lists).
+	Cloudflare,
+}
+
+impl CloudProvider {
+	/// Returns the provider's short display name.
+	#[must_use]
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Aws => "AWS",
+			Self::Gcp => "GCP",
+			Self::Azure => "Azure",
+			Self::Cloudflare => "Cloudflare",
+		}
+	}
+}
+
+impl fmt::Display for CloudProvider {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// A prefix published by a cloud provider, with its labels.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CloudPrefix {
+	/// The published prefix.
+	pub net: IpNet,
+	/// The provider that published it.
+	pub provider: CloudProvider,
+	/// Region or scope (e.g., `us-east-1`), if the feed carries one.
+	pub region: Option<String>,
+	/// Service or service tag (e.g., `EC2`, `Storage.EastUS`), if the feed carries one.
+	pub service: Option<String>,
+}
+
+/// Parses the contents of a provider's published IP range feed.
+///
+/// The expected format depends on `provider`:
+///
+/// * `Aws` - `ip-ranges.json` (`prefixes` and `ipv6_prefixes`).
+/// * `Gcp` - `cloud.json` (`ipv4Prefix` / `ipv6Prefix` entries, `scope` as region).
+/// * `Azure` - Service Tags JSON (`values[].properties.addressPrefixes`, tag name as service).
+/// * `Cloudflare` - one CIDR per line; blank lines and `#` comments are skipped.
+///
+/// # Returns
+///
+/// * `Ok(Vec<CloudPrefix>)` in feed order.
+/// * `Err(NetSemError::InvalidFeed)` if the document or any prefix is malformed.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::cloud::{CloudProvider, parse_feed};
+///
+/// let prefixes = parse_feed(CloudProvider::Cloudflare, "173.245.48.0/20\n2400:cb00::/32\n").unwrap();
+/// assert_eq!(prefixes.len(), 2);
+/// assert_eq!(prefixes[0].provider, CloudProvider::Cloudflare);
+/// ```
+pub fn parse_feed(provider: CloudProvider, input: &str) -> Result<Vec<CloudPrefix>, NetSemError> {
+	match provider {
+		CloudProvider::Aws => parse_aws(input),
+		CloudProvider::Gcp => parse_gcp(input),
+		CloudProvider::Azure => parse_azure(input),
+		CloudProvider::Cloudflare => parse_cloudflare(input),
+	}
+}
+
+/// Reads and parses a provider's IP range feed from a local file.
+///
+/// See [`parse_feed`] for the accepted formats.
+pub fn load_feed(
+	provider: CloudProvider,
+	path: impl AsRef<Path>,
+) -> Result<Vec<CloudPrefix>, NetSemError> {
+	let path = path.as_ref();
+	let input = std::fs::read_to_string(path).map_err(|e| NetSemError::ReadFailed {
+		path: path.display().to_string(),
+		source: e,
+	})?;
+	parse_feed(provider, &input)
+}
+
+/// Builds a longest-prefix-match table from parsed feed entries.
+///
+/// Feeds publish some prefixes more than once (AWS lists a range under both
+/// `AMAZON` and `EC2`), so each prefix maps to every entry for it, in feed order.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::cloud::{CloudProvider, parse_feed, to_table};
+///
+/// let table = to_table(parse_feed(CloudProvider::Cloudflare, "104.16.0.0/13").unwrap());
+/// let (_, hits) = table.lookup("104.16.1.1".parse().unwrap()).unwrap();
+/// assert_eq!(hits[0].provider, CloudProvider::Cloudflare);
+/// assert!(table.lookup("8.8.8.8".parse().unwrap()).is_none());
+/// ```
+#[must_use]
+pub fn to_table(prefixes: impl IntoIterator<Item = CloudPrefix>) -> PrefixTable<Vec<CloudPrefix>> {
+	let mut table: PrefixTable<Vec<CloudPrefix>> = PrefixTable::new();
+	for prefix in prefixes {
+		if let Some(entries) = table.get_mut(&prefix.net) {
+			entries.push(prefix);
+		} else {
+			table.insert(prefix.net, vec![prefix]);
+		}
+	}
+	table
+}
+
+#[derive(Deserialize)]
+struct AwsFeed {
+	prefixes: Vec<AwsPrefix>,
+	#[serde(default)]
+	ipv6_prefixes: Vec<AwsPrefix>,
+}
+
+#[derive(Deserialize)]
+struct AwsPrefix {
+	#[serde(alias = "ipv6_prefix")]
+	ip_prefix: String,
+	region: Option<String>,
+	service: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GcpFeed {
+	prefixes: Vec<GcpPrefix>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GcpPrefix {
+	ipv4_prefix: Option<String>,
+	ipv6_prefix: Option<String>,
+	service: Option<String>,
+	scope: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AzureFeed {
+	values: Vec<AzureTag>,
+}
+
+#[derive(Deserialize)]
+struct AzureTag {
+	name: String,
+	properties: AzureProperties,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureProperties {
+	region: Option<String>,
+	address_prefixes: Vec<String>,
+}
+
+fn parse_json<'a, T: Deserialize<'a>>(
+	provider: CloudProvider,
+	input: &'a str,
+) -> Result<T, NetSemError> {
+	serde_json::from_str(input).map_err(|e| NetSemError::InvalidFeed {
+		provider: provider.name(),
+		reason: e.to_string(),
+	})
+}
+
+fn parse_net(provider: CloudProvider, s: &str) -> Result<IpNet, NetSemError> {
+	s.trim().parse().map_err(|_| NetSemError::InvalidFeed {
+		provider: provider.name(),
+		reason: format!("invalid prefix {s:?}"),
+	})
+}
+
+/// Treats empty strings in feeds as missing labels.
+fn label(s: Option<String>) -> Option<String> {
+	s.filter(|s| !s.is_empty())
+}
+
+fn parse_aws(input: &str) -> Result<Vec<CloudPrefix>, NetSemError> {
+	let feed: AwsFeed = parse_json(CloudProvider::Aws, input)?;
+	feed
+		.prefixes
+		.into_iter()
+		.chain(feed.ipv6_prefixes)
+		.map(|p| {
+			Ok(CloudPrefix {
+				net: parse_net(CloudProvider::Aws, &p.ip_prefix)?,
+				provider: CloudProvider::Aws,
+				region: label(p.region),
+				service: label(p.service),
+			})
+		})
+		.collect()
+}
+
+fn parse_gcp(input: &str) -> Result<Vec<CloudPrefix>, NetSemError> {
+	let feed: GcpFeed = parse_json(CloudProvider::Gcp, input)?;
+	feed
+		.prefixes
+		.into_iter()
+		.map(|p| {
+			let Some(prefix) = p.ipv4_prefix.or(p.ipv6_prefix) else {
+				return Err(NetSemError::InvalidFeed {
+					provider: CloudProvider::Gcp.name(),
+					reason: "entry without ipv4Prefix or ipv6Prefix".to_owned(),
+				});
+			};
+			Ok(CloudPrefix {
+				net: parse_net(CloudProvider::Gcp, &prefix)?,
+				provider: CloudProvider::Gcp,
+				region: label(p.scope),
+				service: label(p.service),
+			})
+		})
+		.collect()
+}
+
+fn parse_azure(input: &str) -> Result<Vec<CloudPrefix>, NetSemError> {
+	let feed: AzureFeed = parse_json(CloudProvider::Azure, input)?;
+	let mut out = Vec::new();
+	for tag in feed.values {
+		let region = label(tag.properties.region);
+		for prefix in &tag.properties.address_prefixes {
+			out.push(CloudPrefix {
+				net: parse_net(CloudProvider::Azure, prefix)?,
+				provider: CloudProvider::Azure,
+				region: region.clone(),
+				service: Some(tag.name.clone()),
+			});
+		}
+	}
+	Ok(out)
+}
+
+fn parse_cloudflare(input: &str) -> Result<Vec<CloudPrefix>, NetSemError> {
+	input
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.map(|line| {
+			Ok(CloudPrefix {
+				net: parse_net(CloudProvider::Cloudflare, line)?,
+				provider: CloudProvider::Cloudflare,
+				region: None,
+				service: None,
+			})
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ip::{IpClass, classify_ip};
+
+	const AWS: &str = r#"{
+		"syncToken": "1700000000",
+		"createDate": "2024-01-01-00-00-00",
+		"prefixes": [
+			{"ip_prefix": "3.5.140.0/22", "region": "ap-northeast-2", "service": "AMAZON", "network_border_group": "ap-northeast-2"},
+			{"ip_prefix": "3.5.140.0/24", "region": "ap-northeast-2", "service": "S3", "network_border_group": "ap-northeast-2"}
+		],
+		"ipv6_prefixes": [
+			{"ipv6_prefix": "2600:1f00::/24", "region": "GLOBAL", "service": "AMAZON", "network_border_group": "GLOBAL"}
+		]
+	}"#;
+
+	const GCP: &str = r#"{
+		"syncToken": "1700000000",
+		"creationTime": "2024-01-01T00:00:00",
+		"prefixes": [
+			{"ipv4Prefix": "34.1.208.0/20", "service": "Google Cloud", "scope": "africa-south1"},
+			{"ipv6Prefix": "2600:1900:8000::/44", "service": "Google Cloud", "scope": "africa-south1"}
+		]
+	}"#;
+
+	const AZURE: &str = r#"{
+		"changeNumber": 1,
+		"cloud": "Public",
+		"values": [
+			{
+				"name": "Storage.EastUS",
+				"id": "Storage.EastUS",
+				"properties": {
+					"changeNumber": 1,
+					"region": "eastus",
+					"regionId": 32,
+					"platform": "Azure",
+					"systemService": "AzureStorage",
+					"addressPrefixes": ["20.38.98.0/24", "2603:1030:20e::/48"],
+					"networkFeatures": ["API"]
+				}
+			},
+			{
+				"name": "AzureCloud",
+				"id": "AzureCloud",
+				"properties": {"changeNumber": 1, "region": "", "regionId": 0, "platform": "Azure", "systemService": "", "addressPrefixes": ["20.0.0.0/11"]}
+			}
+		]
+	}"#;
+
+	#[test]
+	fn test_parse_aws() {
+		let prefixes = parse_feed(CloudProvider::Aws, AWS).unwrap();
+		assert_eq!(prefixes.len(), 3);
+		assert_eq!(prefixes[1].service.as_deref(), Some("S3"));
+		assert_eq!(prefixes[2].net.to_string(), "2600:1f00::/24");
+
+		let table = to_table(prefixes);
+		let (net, hits) = table.lookup("3.5.140.9".parse().unwrap()).unwrap();
+		assert_eq!(net.to_string(), "3.5.140.0/24");
+		assert_eq!(hits[0].region.as_deref(), Some("ap-northeast-2"));
+	}
+
+	#[test]
+	fn test_parse_gcp() {
+		let prefixes = parse_feed(CloudProvider::Gcp, GCP).unwrap();
+		assert_eq!(prefixes.len(), 2);
+		assert_eq!(prefixes[0].region.as_deref(), Some("africa-south1"));
+		assert!(prefixes[1].net.is_ipv6());
+	}
+
+	#[test]
+	fn test_parse_azure() {
+		let prefixes = parse_feed(CloudProvider::Azure, AZURE).unwrap();
+		assert_eq!(prefixes.len(), 3);
+		assert_eq!(prefixes[0].service.as_deref(), Some("Storage.EastUS"));
+		assert_eq!(prefixes[2].region, None);
+
+		let table = to_table(prefixes);
+		let (_, hits) = table.lookup("20.38.98.1".parse().unwrap()).unwrap();
+		assert_eq!(hits[0].service.as_deref(), Some("Storage.EastUS"));
+		let (_, hits) = table.lookup("20.1.0.1".parse().unwrap()).unwrap();
+		assert_eq!(hits[0].service.as_deref(), Some("AzureCloud"));
+	}
+
+	#[test]
+	fn test_to_table_keeps_duplicate_prefixes() {
+		let input = r#"{"prefixes": [
+			{"ip_prefix": "52.95.0.0/20", "region": "us-east-1", "service": "AMAZON"},
+			{"ip_prefix": "52.95.0.0/20", "region": "us-east-1", "service": "EC2"}
+		]}"#;
+		let table = to_table(parse_feed(CloudProvider::Aws, input).unwrap());
+		let (_, hits) = table.lookup("52.95.1.1".parse().unwrap()).unwrap();
+		let services: Vec<_> = hits.iter().map(|p| p.service.as_deref()).collect();
+		assert_eq!(services, [Some("AMAZON"), Some("EC2")]);
+	}
+
+	#[test]
+	fn test_parse_cloudflare() {
+		let input = "# ips-v4\n173.245.48.0/20\n\n103.21.244.0/22\n";
+		let prefixes = parse_feed(CloudProvider::Cloudflare, input).unwrap();
+		assert_eq!(prefixes.len(), 2);
+		assert!(matches!(
+			parse_feed(CloudProvider::Cloudflare, "173.245.48.0/20\nnot-a-prefix").unwrap_err(),
+			NetSemError::InvalidFeed {
+				provider: "Cloudflare",
+				..
+			}
+		));
+	}
+
+	#[test]
+	fn test_invalid_json_feed() {
+		assert!(matches!(
+			parse_feed(CloudProvider::Aws, "{").unwrap_err(),
+			NetSemError::InvalidFeed {
+				provider: "AWS",
+				..
+			}
+		));
+		assert!(parse_feed(CloudProvider::Gcp, r#"{"prefixes": [{"service": "x"}]}"#).is_err());
+	}
+
+	#[test]
+	fn test_non_cloud_classification_untouched() {
+		let table = to_table(parse_feed(CloudProvider::Aws, AWS).unwrap());
+		let ip = "192.168.1.1".parse().unwrap();
+		assert!(table.lookup(ip).is_none());
+		assert_eq!(classify_ip(ip), IpClass::Private);
+	}
+}
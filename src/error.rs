@@ -1,5 +1,10 @@
 /* src/error.rs */
 
+use crate::ip::IpClass;
+#[cfg(feature = "check")]
+use crate::proto::Protocol;
+use alloc::string::String;
+use core::net::Ipv4Addr;
 use thiserror::Error;
 
 /// Errors that can occur within the netsem crate.
@@ -14,11 +19,119 @@ pub enum NetSemError {
 	#[error("Invalid socket address format: {0}")]
 	InvalidSocketAddr(String),
 
+	/// The provided IP prefix (CIDR) string format is invalid.
+	#[error("Invalid IP prefix: {0}")]
+	InvalidPrefix(String),
+
+	/// The provided IP range is malformed, mixes families, or ends before it starts.
+	#[error("Invalid IP range: {0}")]
+	InvalidRange(String),
+
+	/// An IPv6 address plan or allocation request is malformed.
+	#[error("Invalid address plan: {0}")]
+	InvalidPlan(String),
+
+	/// An address pool has no free prefix of the requested length left.
+	#[error("No free /{prefix_len} left in {parent}")]
+	PlanExhausted {
+		/// The pool prefix, in CIDR notation.
+		parent: String,
+		/// The requested prefix length.
+		prefix_len: u8,
+	},
+
+	/// An IPv4 address must not be represented behind a NAT64 prefix.
+	#[error("Refusing to synthesize an IPv6 address for {class} IPv4 address {addr}")]
+	Nat64Excluded {
+		/// The IPv4 address that was refused.
+		addr: Ipv4Addr,
+		/// Its classification.
+		class: IpClass,
+	},
+
+	/// The provided hardware (MAC / EUI-64) address format is invalid.
+	#[error("Invalid hardware address: {0}")]
+	InvalidMac(String),
+
+	/// A generated interface identifier is reserved (RFC 5453) and must not be used.
+	#[error("Reserved IPv6 interface identifier: {0:#018x}")]
+	ReservedIid(u64),
+
+	/// The provided Autonomous System Number is malformed or out of range.
+	#[error("Invalid AS number: {0}")]
+	InvalidAsn(String),
+
+	/// The provided string does not name a known classification.
+	#[error("Unknown class name: {0}")]
+	UnknownClass(String),
+
+	/// A field of a parsed file or feed holds a name outside its fixed vocabulary.
+	#[error("Unknown {kind}: {name}")]
+	UnknownName {
+		/// What was being parsed (e.g. `"delegation status"`).
+		kind: &'static str,
+		/// The rejected name.
+		name: String,
+	},
+
 	/// The provided port is invalid.
 	#[error("Invalid port: {0}")]
 	InvalidPort(u16),
 
+	/// The port is valid but outside the range a port type requires.
+	#[error("Port {port} is outside {min}-{max}")]
+	PortNotInRange {
+		/// The rejected port.
+		port: u16,
+		/// The lowest allowed port.
+		min: u16,
+		/// The highest allowed port.
+		max: u16,
+	},
+
+	/// The provided port string is empty.
+	#[error("Empty port")]
+	EmptyPort,
+
+	/// The provided port string contains whitespace.
+	#[error("Port contains whitespace: {0:?}")]
+	PortWhitespace(String),
+
+	/// The provided port string starts with a `+` or `-` sign.
+	#[error("Port must not be signed: {0}")]
+	SignedPort(String),
+
+	/// The provided port range is malformed or ends before it starts.
+	#[error("Invalid port range: {0}")]
+	InvalidPortRange(String),
+
+	/// An item or protocol prefix of a port list (e.g. `"22,U:53"`) is malformed.
+	#[error("Invalid port list item: {0}")]
+	InvalidPortSet(String),
+
+	/// The provided numeric port is larger than 65535.
+	#[error("Port out of range (0-65535): {0}")]
+	PortOutOfRange(String),
+
+	/// The provided port is neither numeric nor a known service name.
+	#[error("Unknown service name: {0}")]
+	UnknownService(String),
+
+	/// The provided IP protocol name or number is not recognized.
+	#[error("Invalid IP protocol: {0}")]
+	InvalidProtocol(String),
+
+	/// The provided transport-qualified port (e.g. `"53/udp"`) is malformed or names a protocol without ports.
+	#[error("Invalid transport port: {0}")]
+	InvalidTransportPort(String),
+
+	/// The OS check has no socket type for this transport (e.g. DCCP).
+	#[cfg(feature = "check")]
+	#[error("Unsupported transport for socket checks: {0}")]
+	UnsupportedTransport(Protocol),
+
 	/// Failed to bind to the specified address.
+	#[cfg(feature = "std")]
 	#[error("Failed to bind to {addr}: {source}")]
 	BindFailed {
 		/// The address string attempted to bind to.
@@ -28,6 +141,7 @@ pub enum NetSemError {
 	},
 
 	/// Failed to connect to the specified address.
+	#[cfg(feature = "std")]
 	#[error("Failed to connect to {addr}: {source}")]
 	ConnectFailed {
 		/// The address string attempted to connect to.
@@ -35,4 +149,41 @@ pub enum NetSemError {
 		/// The underlying IO error.
 		source: std::io::Error,
 	},
+
+	/// Failed to read the specified file.
+	#[cfg(feature = "std")]
+	#[error("Failed to read {path}: {source}")]
+	ReadFailed {
+		/// The path of the file.
+		path: String,
+		/// The underlying IO error.
+		source: std::io::Error,
+	},
+
+	/// A line of an RIR delegated-statistics file could not be parsed.
+	#[error("Invalid delegated statistics at line {line}: {reason}")]
+	InvalidDelegation {
+		/// The 1-based line number.
+		line: usize,
+		/// Why the line was rejected.
+		reason: String,
+	},
+
+	/// A line of an `/etc/services`-format file could not be parsed.
+	#[error("Invalid services file at line {line}: {reason}")]
+	InvalidServices {
+		/// The 1-based line number.
+		line: usize,
+		/// Why the line was rejected.
+		reason: String,
+	},
+
+	/// A published IP range feed could not be parsed.
+	#[error("Invalid {provider} feed: {reason}")]
+	InvalidFeed {
+		/// The provider whose feed format was expected.
+		provider: &'static str,
+		/// Why the feed was rejected.
+		reason: String,
+	},
 }
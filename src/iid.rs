@@ -0,0 +1,286 @@
+/* src/iid.rs */
+
+use crate::error::NetSemError;
+use crate::net::IpNet;
+use alloc::format;
+use core::net::{IpAddr, Ipv6Addr};
+
+#[cfg(feature = "iid")]
+use hmac::{Hmac, Mac};
+#[cfg(feature = "iid")]
+use sha2::Sha256;
+
+/// Checks whether `iid` is a reserved interface identifier (RFC 5453).
+///
+/// Covers the IANA "Reserved IPv6 Interface Identifiers" registry:
+///
+/// * `0000:0000:0000:0000`, the subnet-router anycast address (RFC 4291).
+/// * `0200:5eff:fe00:0000` to `0200:5eff:feff:ffff`, derived from the IANA
+///   Ethernet block, including the Proxy Mobile IPv6 identifier (RFC 6543).
+/// * `fdff:ffff:ffff:ff80` to `fdff:ffff:ffff:ffff`, the reserved subnet
+///   anycast addresses (RFC 2526).
+///
+/// # Examples
+///
+/// ```
+/// use netsem::iid::is_reserved_iid;
+///
+/// assert!(is_reserved_iid(0));
+/// assert!(is_reserved_iid(0x0200_5eff_fe00_5213));
+/// assert!(is_reserved_iid(0xfdff_ffff_ffff_ff80));
+/// assert!(!is_reserved_iid(0x021a_2bff_fe3c_4d5e));
+/// ```
+#[must_use]
+pub fn is_reserved_iid(iid: u64) -> bool {
+	iid == 0
+		|| (0x0200_5eff_fe00_0000..=0x0200_5eff_feff_ffff).contains(&iid)
+		|| (0xfdff_ffff_ffff_ff80..=0xfdff_ffff_ffff_ffff).contains(&iid)
+}
+
+/// Combines the upper 64 bits of `prefix` with an interface identifier.
+///
+/// # Returns
+///
+/// * `Ok(Ipv6Addr)` if `prefix` is an IPv6 `/64`.
+/// * `Err(NetSemError::InvalidPrefix)` for other prefixes.
+/// * `Err(NetSemError::ReservedIid)` if [`is_reserved_iid`] holds for `iid`.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::iid::with_iid;
+///
+/// let prefix = "2001:db8:1:2::/64".parse().unwrap();
+/// assert_eq!(with_iid(prefix, 0x1234).unwrap().to_string(), "2001:db8:1:2::1234");
+/// assert!(with_iid(prefix, 0).is_err());
+/// ```
+pub fn with_iid(prefix: IpNet, iid: u64) -> Result<Ipv6Addr, NetSemError> {
+	let IpAddr::V6(net) = prefix.network() else {
+		return Err(invalid_prefix(prefix));
+	};
+	if prefix.prefix_len() != 64 {
+		return Err(invalid_prefix(prefix));
+	}
+	if is_reserved_iid(iid) {
+		return Err(NetSemError::ReservedIid(iid));
+	}
+	Ok(Ipv6Addr::from(u128::from(net) | u128::from(iid)))
+}
+
+fn invalid_prefix(prefix: IpNet) -> NetSemError {
+	NetSemError::InvalidPrefix(format!("{prefix} (expected IPv6 /64)"))
+}
+
+/// Generates semantically opaque interface identifiers from a secret key.
+///
+/// Implements the pseudorandom function `F()` of RFC 7217 (stable addresses)
+/// and RFC 8981 section 3.3.2 (hash-based temporary addresses) with
+/// HMAC-SHA-256, taking the first 64 bits of the output. Variable-length
+/// inputs are length-prefixed, so different splits of the same bytes never
+/// collide.
+///
+/// When a generated identifier is reserved or Duplicate Address Detection
+/// fails, increment `dad_counter` and generate again.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::iid::OpaqueIids;
+///
+/// let iids = OpaqueIids::new(b"per-host secret, at least 128 bits");
+/// let prefix = "2001:db8:1:2::/64".parse().unwrap();
+///
+/// let a = iids.stable_address(prefix, b"eth0", b"", 0).unwrap();
+/// assert_eq!(iids.stable_address(prefix, b"eth0", b"", 0).unwrap(), a);
+/// assert_ne!(iids.stable_address(prefix, b"eth1", b"", 0).unwrap(), a);
+/// assert!(prefix.contains(a.into()));
+/// ```
+#[cfg(feature = "iid")]
+#[derive(Clone)]
+pub struct OpaqueIids {
+	mac: Hmac<Sha256>,
+}
+
+#[cfg(feature = "iid")]
+impl core::fmt::Debug for OpaqueIids {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("OpaqueIids").finish_non_exhaustive()
+	}
+}
+
+#[cfg(feature = "iid")]
+impl OpaqueIids {
+	/// Creates a generator from the host's secret key (RFC 7217 recommends at least 128 bits).
+	#[must_use]
+	pub fn new(secret_key: &[u8]) -> Self {
+		Self {
+			mac: Hmac::new_from_slice(secret_key).expect("HMAC accepts keys of any length"),
+		}
+	}
+
+	/// Returns the RFC 7217 stable address for `prefix` on `net_iface`.
+	///
+	/// `net_iface` identifies the interface (e.g., its name or index) and
+	/// `network_id` the attached network (e.g., the Wi-Fi SSID, or empty).
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPrefix)` if `prefix` is not an IPv6 `/64`.
+	/// * `Err(NetSemError::ReservedIid)` if the identifier is reserved; retry with the next `dad_counter`.
+	pub fn stable_address(
+		&self,
+		prefix: IpNet,
+		net_iface: &[u8],
+		network_id: &[u8],
+		dad_counter: u32,
+	) -> Result<Ipv6Addr, NetSemError> {
+		let iid = self.iid(prefix, net_iface, network_id, None, dad_counter)?;
+		with_iid(prefix, iid)
+	}
+
+	/// Returns an RFC 8981 hash-based temporary address for `prefix`.
+	///
+	/// `time` should change every temporary address lifetime (e.g., the
+	/// current time divided by the preferred lifetime), yielding a new
+	/// unlinkable address for each period.
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPrefix)` if `prefix` is not an IPv6 `/64`.
+	/// * `Err(NetSemError::ReservedIid)` if the identifier is reserved; retry with the next `dad_counter`.
+	pub fn temporary_address(
+		&self,
+		prefix: IpNet,
+		net_iface: &[u8],
+		network_id: &[u8],
+		time: u64,
+		dad_counter: u32,
+	) -> Result<Ipv6Addr, NetSemError> {
+		let iid = self.iid(prefix, net_iface, network_id, Some(time), dad_counter)?;
+		with_iid(prefix, iid)
+	}
+
+	fn iid(
+		&self,
+		prefix: IpNet,
+		net_iface: &[u8],
+		network_id: &[u8],
+		time: Option<u64>,
+		dad_counter: u32,
+	) -> Result<u64, NetSemError> {
+		let IpAddr::V6(net) = prefix.network() else {
+			return Err(invalid_prefix(prefix));
+		};
+		let mut mac = self.mac.clone();
+		mac.update(&net.octets()[..8]);
+		for field in [net_iface, network_id] {
+			mac.update(&(field.len() as u64).to_be_bytes());
+			mac.update(field);
+		}
+		if let Some(time) = time {
+			mac.update(&time.to_be_bytes());
+		}
+		mac.update(&dad_counter.to_be_bytes());
+		let digest = mac.finalize().into_bytes();
+		Ok(u64::from_be_bytes(
+			digest[..8].try_into().expect("digest is 32 bytes"),
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_reserved_iids() {
+		for iid in [
+			0,
+			0x0200_5eff_fe00_0000,
+			0x0200_5eff_fe00_5212,
+			0x0200_5eff_fe00_5213,
+			0x0200_5eff_feff_ffff,
+			0xfdff_ffff_ffff_ff80,
+			0xfdff_ffff_ffff_ffff,
+		] {
+			assert!(is_reserved_iid(iid), "{iid:#x}");
+		}
+		for iid in [
+			1,
+			0x0200_5eff_fdff_ffff,
+			0x0200_5eff_ff00_0000,
+			0xfdff_ffff_ffff_ff7f,
+			u64::MAX,
+		] {
+			assert!(!is_reserved_iid(iid), "{iid:#x}");
+		}
+	}
+
+	#[test]
+	fn test_with_iid() {
+		let prefix: IpNet = "2001:db8::/64".parse().unwrap();
+		assert_eq!(
+			with_iid(prefix, 0xabcd).unwrap(),
+			"2001:db8::abcd".parse::<Ipv6Addr>().unwrap()
+		);
+		assert!(matches!(
+			with_iid(prefix, 0x0200_5eff_fe00_5213),
+			Err(NetSemError::ReservedIid(0x0200_5eff_fe00_5213))
+		));
+		assert!(with_iid("2001:db8::/96".parse().unwrap(), 1).is_err());
+		assert!(matches!(
+			with_iid("2001:db8::/48".parse().unwrap(), 1),
+			Err(NetSemError::InvalidPrefix(_))
+		));
+		assert!(with_iid("10.0.0.0/8".parse().unwrap(), 1).is_err());
+	}
+
+	#[test]
+	#[cfg(feature = "iid")]
+	fn test_opaque_iids() {
+		let iids = OpaqueIids::new(b"0123456789abcdef");
+		let prefix: IpNet = "2001:db8:1:2::/64".parse().unwrap();
+		let other: IpNet = "2001:db8:1:3::/64".parse().unwrap();
+
+		let stable = iids.stable_address(prefix, b"eth0", b"", 0).unwrap();
+		assert!(prefix.contains(stable.into()));
+		// Stable across calls, different per prefix, counter, network and key.
+		assert_eq!(
+			iids.stable_address(prefix, b"eth0", b"", 0).unwrap(),
+			stable
+		);
+		let stable_iid = u128::from(stable) as u64;
+		let other_iid = u128::from(iids.stable_address(other, b"eth0", b"", 0).unwrap()) as u64;
+		assert_ne!(other_iid, stable_iid);
+		assert_ne!(
+			iids.stable_address(prefix, b"eth0", b"", 1).unwrap(),
+			stable
+		);
+		assert_ne!(
+			iids.stable_address(prefix, b"eth0", b"home", 0).unwrap(),
+			stable
+		);
+		assert_ne!(
+			iids.stable_address(prefix, b"eth", b"0", 0).unwrap(),
+			stable
+		);
+		assert_ne!(
+			OpaqueIids::new(b"another key")
+				.stable_address(prefix, b"eth0", b"", 0)
+				.unwrap(),
+			stable
+		);
+
+		let t0 = iids.temporary_address(prefix, b"eth0", b"", 0, 0).unwrap();
+		let t1 = iids.temporary_address(prefix, b"eth0", b"", 1, 0).unwrap();
+		assert_ne!(t0, t1);
+		assert_ne!(t0, stable);
+		assert!(prefix.contains(t1.into()));
+
+		assert!(
+			iids
+				.stable_address("10.0.0.0/8".parse().unwrap(), b"eth0", b"", 0)
+				.is_err()
+		);
+	}
+}
@@ -1,12 +1,24 @@
 /* src/ip.rs */
 
 use crate::error::NetSemError;
-use std::net::IpAddr;
+use crate::mac::MacAddr;
+use crate::nat64::Nat64Prefix;
+use crate::net::IpNet;
+use alloc::borrow::ToOwned;
+use core::fmt;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
 
 /// Classification of an IP address.
+///
+/// The string form (used by `Display`, `FromStr` and serde) is the kebab-case
+/// variant name, e.g. `"link-local"` or `"shared-address"`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum IpClass {
 	/// Loopback address (e.g., 127.0.0.1, ::1).
 	Loopback,
@@ -28,6 +40,261 @@ pub enum IpClass {
 	SharedAddress,
 	/// Benchmarking address (198.18.0.0/15, RFC 2544).
 	Benchmarking,
+	/// IETF protocol assignment (192.0.0.0/24, RFC 6890), e.g. DS-Lite or PCP anycast.
+	ProtocolAssignment,
+}
+
+impl IpClass {
+	/// Every class, in declaration order.
+	pub const ALL: &'static [Self] = &[
+		Self::Loopback,
+		Self::Private,
+		Self::LinkLocal,
+		Self::Global,
+		Self::Multicast,
+		Self::Unspecified,
+		Self::Broadcast,
+		Self::Documentation,
+		Self::SharedAddress,
+		Self::Benchmarking,
+		Self::ProtocolAssignment,
+	];
+
+	/// Returns the stable kebab-case name of the class.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::IpClass;
+	///
+	/// assert_eq!(IpClass::LinkLocal.as_str(), "link-local");
+	/// assert_eq!("shared-address".parse::<IpClass>().unwrap(), IpClass::SharedAddress);
+	/// ```
+	#[must_use]
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Loopback => "loopback",
+			Self::Private => "private",
+			Self::LinkLocal => "link-local",
+			Self::Global => "global",
+			Self::Multicast => "multicast",
+			Self::Unspecified => "unspecified",
+			Self::Broadcast => "broadcast",
+			Self::Documentation => "documentation",
+			Self::SharedAddress => "shared-address",
+			Self::Benchmarking => "benchmarking",
+			Self::ProtocolAssignment => "protocol-assignment",
+		}
+	}
+}
+
+impl fmt::Display for IpClass {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for IpClass {
+	type Err = NetSemError;
+
+	/// Parses the kebab-case name produced by [`IpClass::as_str`].
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"loopback" => Ok(Self::Loopback),
+			"private" => Ok(Self::Private),
+			"link-local" => Ok(Self::LinkLocal),
+			"global" => Ok(Self::Global),
+			"multicast" => Ok(Self::Multicast),
+			"unspecified" => Ok(Self::Unspecified),
+			"broadcast" => Ok(Self::Broadcast),
+			"documentation" => Ok(Self::Documentation),
+			"shared-address" => Ok(Self::SharedAddress),
+			"benchmarking" => Ok(Self::Benchmarking),
+			"protocol-assignment" => Ok(Self::ProtocolAssignment),
+			_ => Err(NetSemError::UnknownClass(s.to_owned())),
+		}
+	}
+}
+
+/// A set of [`IpClass`] values, stored as a bitmask.
+///
+/// Returned by [`classify_ip_all`]. With the `serde` feature it serializes
+/// as a list of class names, so configuration can say `deny: [private, loopback]`.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{IpClass, IpClassSet};
+///
+/// let deny = IpClassSet::from([IpClass::Private, IpClass::Loopback]);
+/// assert!(deny.contains(IpClass::Private));
+/// assert!(!deny.contains(IpClass::Global));
+/// assert_eq!(deny.iter().collect::<Vec<_>>(), [IpClass::Loopback, IpClass::Private]);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct IpClassSet(u16);
+
+impl IpClassSet {
+	/// The empty set.
+	pub const EMPTY: Self = Self(0);
+
+	/// Creates an empty set.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self::EMPTY
+	}
+
+	const fn bit(class: IpClass) -> u16 {
+		1 << class as u16
+	}
+
+	/// Returns `true` if `class` is in the set.
+	#[must_use]
+	pub const fn contains(self, class: IpClass) -> bool {
+		self.0 & Self::bit(class) != 0
+	}
+
+	/// Adds `class` to the set.
+	pub fn insert(&mut self, class: IpClass) {
+		self.0 |= Self::bit(class);
+	}
+
+	/// Removes `class` from the set.
+	pub fn remove(&mut self, class: IpClass) {
+		self.0 &= !Self::bit(class);
+	}
+
+	/// Returns `true` if the set holds no classes.
+	#[must_use]
+	pub const fn is_empty(self) -> bool {
+		self.0 == 0
+	}
+
+	/// Returns the number of classes in the set.
+	#[must_use]
+	pub const fn len(self) -> usize {
+		self.0.count_ones() as usize
+	}
+
+	/// Returns `true` if the two sets share at least one class.
+	#[must_use]
+	pub const fn intersects(self, other: Self) -> bool {
+		self.0 & other.0 != 0
+	}
+
+	/// Returns `true` if every class in `self` is also in `other`.
+	#[must_use]
+	pub const fn is_subset(self, other: Self) -> bool {
+		self.0 & !other.0 == 0
+	}
+
+	/// Iterates over the classes in [`IpClass::ALL`] order.
+	pub fn iter(self) -> impl Iterator<Item = IpClass> {
+		IpClass::ALL
+			.iter()
+			.copied()
+			.filter(move |&c| self.contains(c))
+	}
+}
+
+impl fmt::Debug for IpClassSet {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_set().entries(self.iter()).finish()
+	}
+}
+
+impl From<IpClass> for IpClassSet {
+	fn from(class: IpClass) -> Self {
+		Self(Self::bit(class))
+	}
+}
+
+impl<const N: usize> From<[IpClass; N]> for IpClassSet {
+	fn from(classes: [IpClass; N]) -> Self {
+		classes.into_iter().collect()
+	}
+}
+
+impl From<&[IpClass]> for IpClassSet {
+	fn from(classes: &[IpClass]) -> Self {
+		classes.iter().copied().collect()
+	}
+}
+
+impl FromIterator<IpClass> for IpClassSet {
+	fn from_iter<I: IntoIterator<Item = IpClass>>(iter: I) -> Self {
+		let mut set = Self::new();
+		set.extend(iter);
+		set
+	}
+}
+
+impl Extend<IpClass> for IpClassSet {
+	fn extend<I: IntoIterator<Item = IpClass>>(&mut self, iter: I) {
+		for class in iter {
+			self.insert(class);
+		}
+	}
+}
+
+impl core::ops::BitOr for IpClassSet {
+	type Output = Self;
+
+	fn bitor(self, rhs: Self) -> Self {
+		Self(self.0 | rhs.0)
+	}
+}
+
+impl core::ops::BitOrAssign for IpClassSet {
+	fn bitor_assign(&mut self, rhs: Self) {
+		self.0 |= rhs.0;
+	}
+}
+
+impl core::ops::BitAnd for IpClassSet {
+	type Output = Self;
+
+	fn bitand(self, rhs: Self) -> Self {
+		Self(self.0 & rhs.0)
+	}
+}
+
+impl core::ops::Sub for IpClassSet {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self {
+		Self(self.0 & !rhs.0)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for IpClassSet {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(self.iter())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for IpClassSet {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let classes = <alloc::vec::Vec<IpClass>>::deserialize(deserializer)?;
+		Ok(classes.into_iter().collect())
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for IpClassSet {
+	fn schema_name() -> alloc::borrow::Cow<'static, str> {
+		"IpClassSet".into()
+	}
+
+	fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		schemars::json_schema!({
+			"type": "array",
+			"items": generator.subschema_for::<IpClass>(),
+			"uniqueItems": true
+		})
+	}
 }
 
 /// Parses a string into an IP address.
@@ -58,7 +325,12 @@ pub fn parse_ip(s: &str) -> Result<IpAddr, NetSemError> {
 
 /// Classifies an IP address into a high-level category.
 ///
-/// Priority: Loopback -> Unspecified -> Multicast -> Broadcast -> LinkLocal -> Documentation -> Private -> Global.
+/// Priority: Loopback -> Unspecified -> Multicast -> Broadcast -> LinkLocal -> Documentation ->
+/// SharedAddress -> Benchmarking -> ProtocolAssignment -> Private -> Global.
+///
+/// Every address in `192.0.0.0/24` is `ProtocolAssignment`, including the
+/// globally reachable `192.0.0.9` and `192.0.0.10`; [`classify_ip_all`] adds
+/// `Global` for those two.
 ///
 /// # Arguments
 ///
@@ -112,6 +384,10 @@ pub fn classify_ip(ip: IpAddr) -> IpClass {
 					return IpClass::Benchmarking;
 				}
 			}
+			// IETF Protocol Assignments: 192.0.0.0/24 (RFC 6890)
+			if ipv4.octets()[..3] == [192, 0, 0] {
+				return IpClass::ProtocolAssignment;
+			}
 			if ipv4.is_private() {
 				return IpClass::Private;
 			}
@@ -140,6 +416,105 @@ pub fn classify_ip(ip: IpAddr) -> IpClass {
 	IpClass::Global
 }
 
+/// Returns every class an IP address belongs to.
+///
+/// Unlike [`classify_ip`], which picks a single winner by priority, this
+/// reports all applicable labels. The result always contains
+/// `classify_ip(ip)`, plus:
+///
+/// * For IPv6 addresses embedding an IPv4 address (IPv4-mapped `::ffff:0:0/96`,
+///   NAT64 `64:ff9b::/96` and `64:ff9b:1::/48`, 6to4 `2002::/16`), every class
+///   of the embedded address.
+/// * For multicast, the scope: IPv4 `224.0.0.0/24` and IPv6 link-local scope
+///   add `LinkLocal`, IPv6 interface-local scope adds `Loopback`, IPv6
+///   site/organization-local scope adds `Private`, and IPv6 global scope adds `Global`.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{IpClass, classify_ip_all, parse_ip};
+///
+/// let nat64 = classify_ip_all(parse_ip("64:ff9b::192.0.2.33").unwrap());
+/// assert!(nat64.contains(IpClass::Global));
+/// assert!(nat64.contains(IpClass::Documentation));
+///
+/// let pcp = classify_ip_all(parse_ip("192.0.0.9").unwrap());
+/// assert!(pcp.contains(IpClass::ProtocolAssignment) && pcp.contains(IpClass::Global));
+///
+/// let mdns = classify_ip_all(parse_ip("ff02::fb").unwrap());
+/// assert!(mdns.contains(IpClass::Multicast) && mdns.contains(IpClass::LinkLocal));
+/// ```
+#[must_use]
+pub fn classify_ip_all(ip: IpAddr) -> IpClassSet {
+	let mut set = IpClassSet::from(classify_ip(ip));
+	match ip {
+		IpAddr::V4(ipv4) => {
+			// Local Network Control Block: 224.0.0.0/24 (RFC 5771), never forwarded.
+			if ipv4.octets()[..3] == [224, 0, 0] {
+				set.insert(IpClass::LinkLocal);
+			}
+			// Globally reachable anycast in 192.0.0.0/24 (IANA special-purpose registry).
+			if matches!(ipv4.octets(), [192, 0, 0, 9 | 10]) {
+				set.insert(IpClass::Global);
+			}
+		}
+		IpAddr::V6(ipv6) => {
+			if ipv6.is_multicast() {
+				// Scope is the low nibble of the second octet (RFC 7346).
+				match ipv6.segments()[0] & 0x000f {
+					0x1 => set.insert(IpClass::Loopback),
+					0x2 => set.insert(IpClass::LinkLocal),
+					0x5 | 0x8 => set.insert(IpClass::Private),
+					0xe => set.insert(IpClass::Global),
+					_ => {}
+				}
+			}
+			if let Some(ipv4) = embedded_ipv4(ipv6) {
+				set |= classify_ip_all(IpAddr::V4(ipv4));
+			}
+		}
+	}
+	set
+}
+
+/// Returns the IPv4 address carried inside an IPv6 transition address.
+///
+/// Recognizes IPv4-mapped (`::ffff:0:0/96`), NAT64 well-known and local-use
+/// prefixes (`64:ff9b::/96`, `64:ff9b:1::/48`, RFC 6052 / RFC 8215), and
+/// 6to4 (`2002::/16`, RFC 3056). Local-use addresses are read with the
+/// RFC 6052 `/48` layout, as [`Nat64Prefix`](crate::Nat64Prefix) embeds them.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::embedded_ipv4;
+///
+/// let ip = embedded_ipv4("::ffff:10.0.0.1".parse().unwrap()).unwrap();
+/// assert_eq!(ip.to_string(), "10.0.0.1");
+/// assert!(embedded_ipv4("2001:db8::1".parse().unwrap()).is_none());
+/// ```
+#[must_use]
+pub fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+	let bits = u128::from(ip);
+	let seg = ip.segments();
+	let is_mapped = bits >> 32 == 0xffff;
+	let is_nat64 = bits >> 32 == u128::from(Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0, 0)) >> 32;
+	if is_mapped || is_nat64 {
+		return Some(Ipv4Addr::from(bits as u32));
+	}
+	if seg[..3] == [0x64, 0xff9b, 0x0001] {
+		let local = IpNet::new(
+			IpAddr::V6(Ipv6Addr::new(0x64, 0xff9b, 1, 0, 0, 0, 0, 0)),
+			48,
+		);
+		return local.and_then(Nat64Prefix::new).ok()?.extract(ip);
+	}
+	if seg[0] == 0x2002 {
+		return Some(Ipv4Addr::from((bits >> 80) as u32));
+	}
+	None
+}
+
 /// Checks if a string is a valid IP address syntax.
 ///
 /// Does NOT perform DNS lookups.
@@ -157,10 +532,460 @@ pub fn is_valid_ip_literal(s: &str) -> bool {
 	s.parse::<IpAddr>().is_ok()
 }
 
+/// Maps a multicast group to its Ethernet destination MAC address.
+///
+/// * IPv4 (RFC 1112): `01:00:5e` followed by the low 23 bits of the group.
+///   Five bits of the group are dropped, so 32 groups share each MAC; see
+///   [`ipv4_multicast_aliases`].
+/// * IPv6 (RFC 2464): `33:33` followed by the low 32 bits of the group.
+///
+/// Returns `None` for addresses that are not multicast.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::multicast_mac;
+///
+/// let mdns = multicast_mac("224.0.0.251".parse().unwrap()).unwrap();
+/// assert_eq!(mdns.to_string(), "01:00:5e:00:00:fb");
+///
+/// let all_nodes = multicast_mac("ff02::1".parse().unwrap()).unwrap();
+/// assert_eq!(all_nodes.to_string(), "33:33:00:00:00:01");
+///
+/// assert!(multicast_mac("10.0.0.1".parse().unwrap()).is_none());
+/// ```
+#[must_use]
+pub fn multicast_mac(ip: IpAddr) -> Option<MacAddr> {
+	if !ip.is_multicast() {
+		return None;
+	}
+	match ip {
+		IpAddr::V4(v4) => {
+			let [_, b, c, d] = v4.octets();
+			Some(MacAddr::new([0x01, 0x00, 0x5e, b & 0x7f, c, d]))
+		}
+		IpAddr::V6(v6) => {
+			let [.., a, b, c, d] = v6.octets();
+			Some(MacAddr::new([0x33, 0x33, a, b, c, d]))
+		}
+	}
+}
+
+/// Returns the 32 IPv4 multicast groups that share `ip`'s Ethernet MAC address.
+///
+/// The IPv4-to-MAC mapping keeps only the low 23 bits of the group, so
+/// every MAC is ambiguous across 32 groups. The result is in ascending order
+/// and includes `ip` itself. Returns `None` if `ip` is not multicast.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ipv4_multicast_aliases;
+///
+/// let aliases = ipv4_multicast_aliases("224.0.0.1".parse().unwrap()).unwrap();
+/// assert_eq!(aliases[0].to_string(), "224.0.0.1");
+/// assert_eq!(aliases[1].to_string(), "224.128.0.1");
+/// assert_eq!(aliases[31].to_string(), "239.128.0.1");
+/// ```
+#[must_use]
+pub fn ipv4_multicast_aliases(ip: Ipv4Addr) -> Option<[Ipv4Addr; 32]> {
+	if !ip.is_multicast() {
+		return None;
+	}
+	let low = u32::from(ip) & 0x007f_ffff;
+	Some(core::array::from_fn(|i| {
+		Ipv4Addr::from(0xe000_0000 | ((i as u32) << 23) | low)
+	}))
+}
+
+/// Returns the solicited-node multicast address (`ff02::1:ffXX:XXXX`, RFC 4291) for `ip`.
+///
+/// The group is formed from the low 24 bits of the address and is what
+/// Neighbor Discovery and DAD send to. Returns `None` for multicast and
+/// unspecified addresses, which have no solicited-node group.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::solicited_node;
+///
+/// let ip = "2001:db8::2aa:ff:fe28:9c5a".parse().unwrap();
+/// assert_eq!(solicited_node(ip).unwrap().to_string(), "ff02::1:ff28:9c5a");
+/// ```
+#[must_use]
+pub fn solicited_node(ip: Ipv6Addr) -> Option<Ipv6Addr> {
+	if ip.is_multicast() || ip.is_unspecified() {
+		return None;
+	}
+	let prefix = u128::from(Ipv6Addr::new(0xff02, 0, 0, 0, 0, 1, 0xff00, 0));
+	Some(Ipv6Addr::from(prefix | (u128::from(ip) & 0x00ff_ffff)))
+}
+
+/// Checks whether `ip` is a solicited-node multicast address (`ff02::1:ff00:0/104`).
+#[must_use]
+pub fn is_solicited_node(ip: Ipv6Addr) -> bool {
+	u128::from(ip) >> 24 == u128::from(Ipv6Addr::new(0xff02, 0, 0, 0, 0, 1, 0xff00, 0)) >> 24
+}
+
+/// Prefixes that `classify_ip` assigns wholesale to a single class.
+const CLASS_PREFIXES: &[(IpClass, IpAddr, u8)] = &[
+	(
+		IpClass::Loopback,
+		IpAddr::V4(Ipv4Addr::new(127, 0, 0, 0)),
+		8,
+	),
+	(IpClass::Loopback, IpAddr::V6(Ipv6Addr::LOCALHOST), 128),
+	(IpClass::Unspecified, IpAddr::V4(Ipv4Addr::UNSPECIFIED), 32),
+	(IpClass::Unspecified, IpAddr::V6(Ipv6Addr::UNSPECIFIED), 128),
+	(
+		IpClass::Multicast,
+		IpAddr::V4(Ipv4Addr::new(224, 0, 0, 0)),
+		4,
+	),
+	(
+		IpClass::Multicast,
+		IpAddr::V6(Ipv6Addr::new(0xff00, 0, 0, 0, 0, 0, 0, 0)),
+		8,
+	),
+	(IpClass::Broadcast, IpAddr::V4(Ipv4Addr::BROADCAST), 32),
+	(
+		IpClass::LinkLocal,
+		IpAddr::V4(Ipv4Addr::new(169, 254, 0, 0)),
+		16,
+	),
+	(
+		IpClass::LinkLocal,
+		IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0)),
+		10,
+	),
+	(
+		IpClass::Documentation,
+		IpAddr::V4(Ipv4Addr::new(192, 0, 2, 0)),
+		24,
+	),
+	(
+		IpClass::Documentation,
+		IpAddr::V4(Ipv4Addr::new(198, 51, 100, 0)),
+		24,
+	),
+	(
+		IpClass::Documentation,
+		IpAddr::V4(Ipv4Addr::new(203, 0, 113, 0)),
+		24,
+	),
+	(
+		IpClass::Documentation,
+		IpAddr::V6(Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, 0, 0)),
+		32,
+	),
+	(
+		IpClass::SharedAddress,
+		IpAddr::V4(Ipv4Addr::new(100, 64, 0, 0)),
+		10,
+	),
+	(
+		IpClass::Benchmarking,
+		IpAddr::V4(Ipv4Addr::new(198, 18, 0, 0)),
+		15,
+	),
+	(
+		IpClass::ProtocolAssignment,
+		IpAddr::V4(Ipv4Addr::new(192, 0, 0, 0)),
+		24,
+	),
+	(IpClass::Private, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8),
+	(
+		IpClass::Private,
+		IpAddr::V4(Ipv4Addr::new(172, 16, 0, 0)),
+		12,
+	),
+	(
+		IpClass::Private,
+		IpAddr::V4(Ipv4Addr::new(192, 168, 0, 0)),
+		16,
+	),
+	(
+		IpClass::Private,
+		IpAddr::V6(Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 0)),
+		7,
+	),
+];
+
+/// Returns the prefixes whose every address `classify_ip` maps to `class`.
+///
+/// `IpClass::Global` has no such prefixes: it is the fallback for addresses
+/// outside every other range.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{IpClass, class_prefixes, classify_ip};
+///
+/// let nets: Vec<_> = class_prefixes(IpClass::SharedAddress).collect();
+/// assert_eq!(nets[0].to_string(), "100.64.0.0/10");
+/// assert_eq!(classify_ip(nets[0].last()), IpClass::SharedAddress);
+/// assert_eq!(class_prefixes(IpClass::Global).count(), 0);
+/// ```
+pub fn class_prefixes(class: IpClass) -> impl Iterator<Item = IpNet> {
+	CLASS_PREFIXES
+		.iter()
+		.filter(move |(c, _, _)| *c == class)
+		.filter_map(|&(_, addr, len)| IpNet::new(addr, len).ok())
+}
+
+/// Largest numeric value of the address family of `ip`.
+fn family_max(ip: IpAddr) -> u128 {
+	if ip.is_ipv4() {
+		u128::from(u32::MAX)
+	} else {
+		u128::MAX
+	}
+}
+
+/// Adds `n` to an address, staying within its family.
+///
+/// # Returns
+///
+/// * `Some(IpAddr)` of the same family as `ip`.
+/// * `None` if the result would pass `255.255.255.255` (IPv4) or
+///   `ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff` (IPv6).
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip::checked_add;
+///
+/// let ip = checked_add("10.0.0.250".parse().unwrap(), 1000).unwrap();
+/// assert_eq!(ip.to_string(), "10.0.4.226");
+/// assert_eq!(checked_add("255.255.255.255".parse().unwrap(), 1), None);
+/// ```
+#[must_use]
+pub fn checked_add(ip: IpAddr, n: u128) -> Option<IpAddr> {
+	ip_to_bits(ip)
+		.checked_add(n)
+		.filter(|&bits| bits <= family_max(ip))
+		.map(|bits| bits_to_ip(bits, ip.is_ipv4()))
+}
+
+/// Subtracts `n` from an address, returning `None` below `0.0.0.0` / `::`.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip::checked_sub;
+///
+/// let ip = checked_sub("10.0.1.0".parse().unwrap(), 1).unwrap();
+/// assert_eq!(ip.to_string(), "10.0.0.255");
+/// assert_eq!(checked_sub("::".parse().unwrap(), 1), None);
+/// ```
+#[must_use]
+pub fn checked_sub(ip: IpAddr, n: u128) -> Option<IpAddr> {
+	ip_to_bits(ip)
+		.checked_sub(n)
+		.map(|bits| bits_to_ip(bits, ip.is_ipv4()))
+}
+
+/// Adds `n` to an address, clamping at the highest address of its family.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip::saturating_add;
+///
+/// let ip = saturating_add("255.255.255.200".parse().unwrap(), 1000);
+/// assert_eq!(ip.to_string(), "255.255.255.255");
+/// ```
+#[must_use]
+pub fn saturating_add(ip: IpAddr, n: u128) -> IpAddr {
+	let bits = ip_to_bits(ip).saturating_add(n).min(family_max(ip));
+	bits_to_ip(bits, ip.is_ipv4())
+}
+
+/// Subtracts `n` from an address, clamping at `0.0.0.0` / `::`.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip::saturating_sub;
+///
+/// let ip = saturating_sub("0.0.0.10".parse().unwrap(), 1000);
+/// assert_eq!(ip.to_string(), "0.0.0.0");
+/// ```
+#[must_use]
+pub fn saturating_sub(ip: IpAddr, n: u128) -> IpAddr {
+	bits_to_ip(ip_to_bits(ip).saturating_sub(n), ip.is_ipv4())
+}
+
+/// Returns the address after `ip`, or `None` at the end of its family.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip;
+///
+/// let next = ip::next("192.0.2.255".parse().unwrap()).unwrap();
+/// assert_eq!(next.to_string(), "192.0.3.0");
+/// ```
+#[must_use]
+pub fn next(ip: IpAddr) -> Option<IpAddr> {
+	checked_add(ip, 1)
+}
+
+/// Returns the address before `ip`, or `None` at the start of its family.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip;
+///
+/// let prev = ip::prev("2001:db8::1:0".parse().unwrap()).unwrap();
+/// assert_eq!(prev.to_string(), "2001:db8::ffff");
+/// ```
+#[must_use]
+pub fn prev(ip: IpAddr) -> Option<IpAddr> {
+	checked_sub(ip, 1)
+}
+
+/// Returns the number of addresses between `a` and `b` (`|b - a|`).
+///
+/// # Returns
+///
+/// * `Some(u128)` if both addresses are of the same family.
+/// * `None` if one is IPv4 and the other IPv6 (including IPv4-mapped forms).
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip::distance;
+///
+/// let a = "10.0.0.0".parse().unwrap();
+/// let b = "10.0.1.0".parse().unwrap();
+/// assert_eq!(distance(a, b), Some(256));
+/// assert_eq!(distance(b, a), Some(256));
+/// assert_eq!(distance(a, "::1".parse().unwrap()), None);
+/// ```
+#[must_use]
+pub fn distance(a: IpAddr, b: IpAddr) -> Option<u128> {
+	(a.is_ipv4() == b.is_ipv4()).then(|| ip_to_bits(a).abs_diff(ip_to_bits(b)))
+}
+
+/// Bitwise AND of an address with a mask of the same family.
+///
+/// Returns `None` if the families differ.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip::bitand;
+///
+/// let ip = "192.0.2.77".parse().unwrap();
+/// let mask = "255.255.255.192".parse().unwrap();
+/// assert_eq!(bitand(ip, mask), Some("192.0.2.64".parse().unwrap()));
+/// ```
+#[must_use]
+pub fn bitand(ip: IpAddr, mask: IpAddr) -> Option<IpAddr> {
+	(ip.is_ipv4() == mask.is_ipv4())
+		.then(|| bits_to_ip(ip_to_bits(ip) & ip_to_bits(mask), ip.is_ipv4()))
+}
+
+/// Bitwise OR of an address with a mask of the same family.
+///
+/// Returns `None` if the families differ.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip::bitor;
+///
+/// let ip = "192.0.2.77".parse().unwrap();
+/// let hostmask = "0.0.0.63".parse().unwrap();
+/// assert_eq!(bitor(ip, hostmask), Some("192.0.2.127".parse().unwrap()));
+/// ```
+#[must_use]
+pub fn bitor(ip: IpAddr, mask: IpAddr) -> Option<IpAddr> {
+	(ip.is_ipv4() == mask.is_ipv4())
+		.then(|| bits_to_ip(ip_to_bits(ip) | ip_to_bits(mask), ip.is_ipv4()))
+}
+
+/// Bitwise NOT of an address within its family (e.g., netmask to hostmask).
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip::bitnot;
+///
+/// let mask = "255.255.255.192".parse().unwrap();
+/// assert_eq!(bitnot(mask).to_string(), "0.0.0.63");
+/// ```
+#[must_use]
+pub fn bitnot(ip: IpAddr) -> IpAddr {
+	bits_to_ip(!ip_to_bits(ip) & family_max(ip), ip.is_ipv4())
+}
+
+/// Total ordering of addresses that groups IPv4-mapped IPv6 addresses with IPv4.
+///
+/// The order is:
+///
+/// 1. IPv4 addresses and IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`), by
+///    their IPv4 value. When an address appears in both forms, the IPv4 form
+///    comes first.
+/// 2. All other IPv6 addresses, numerically.
+///
+/// Distinct addresses never compare equal, so the ordering is consistent
+/// with `Eq` and suitable for `sort_by`, `BTreeMap` keys (via a wrapper) or
+/// deduplication. Unlike the derived `Ord` on `IpAddr`, `::ffff:10.0.0.1`
+/// sorts next to `10.0.0.1` rather than after every IPv4 address.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ip::cmp_ip;
+/// use std::net::IpAddr;
+///
+/// let mut ips: Vec<IpAddr> = ["::1", "10.0.0.2", "::ffff:10.0.0.1", "10.0.0.1"]
+///     .iter()
+///     .map(|s| s.parse().unwrap())
+///     .collect();
+/// ips.sort_by(|a, b| cmp_ip(*a, *b));
+/// let sorted: Vec<String> = ips.iter().map(ToString::to_string).collect();
+/// assert_eq!(sorted, ["10.0.0.1", "::ffff:10.0.0.1", "10.0.0.2", "::1"]);
+/// ```
+#[must_use]
+pub fn cmp_ip(a: IpAddr, b: IpAddr) -> core::cmp::Ordering {
+	fn key(ip: IpAddr) -> (bool, u128, bool) {
+		match ip {
+			IpAddr::V4(v4) => (false, u128::from(u32::from(v4)), false),
+			IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+				Some(v4) => (false, u128::from(u32::from(v4)), true),
+				None => (true, u128::from(v6), false),
+			},
+		}
+	}
+	key(a).cmp(&key(b))
+}
+
+/// Returns the numeric value of an address, widened to `u128`.
+pub(crate) fn ip_to_bits(ip: IpAddr) -> u128 {
+	match ip {
+		IpAddr::V4(v4) => u128::from(u32::from(v4)),
+		IpAddr::V6(v6) => u128::from(v6),
+	}
+}
+
+/// Builds an address of the requested family from its numeric value.
+///
+/// For IPv4, only the low 32 bits of `bits` are used.
+pub(crate) fn bits_to_ip(bits: u128, ipv4: bool) -> IpAddr {
+	if ipv4 {
+		IpAddr::V4(Ipv4Addr::from(bits as u32))
+	} else {
+		IpAddr::V6(Ipv6Addr::from(bits))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use std::net::{Ipv4Addr, Ipv6Addr};
 
 	#[test]
 	fn test_parse_ip() {
@@ -245,6 +1070,14 @@ mod tests {
 			IpClass::Private
 		);
 
+		// ProtocolAssignment, including the globally reachable anycast addresses
+		for last in [0, 8, 9, 10, 255] {
+			assert_eq!(
+				classify_ip(IpAddr::V4(Ipv4Addr::new(192, 0, 0, last))),
+				IpClass::ProtocolAssignment
+			);
+		}
+
 		// SharedAddress (CGNAT)
 		assert_eq!(
 			classify_ip(IpAddr::V4(Ipv4Addr::new(100, 64, 0, 1))),
@@ -284,6 +1117,166 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_ip_class_str_round_trip() {
+		for &class in IpClass::ALL {
+			assert_eq!(class.to_string().parse::<IpClass>().unwrap(), class);
+		}
+		assert_eq!(IpClass::SharedAddress.to_string(), "shared-address");
+		assert!(matches!(
+			"LinkLocal".parse::<IpClass>().unwrap_err(),
+			NetSemError::UnknownClass(_)
+		));
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn test_ip_class_serde() {
+		let json = serde_json::to_string(&[IpClass::Private, IpClass::LinkLocal]).unwrap();
+		assert_eq!(json, r#"["private","link-local"]"#);
+		let parsed: Vec<IpClass> = serde_json::from_str(r#"["loopback","shared-address"]"#).unwrap();
+		assert_eq!(parsed, [IpClass::Loopback, IpClass::SharedAddress]);
+	}
+
+	#[test]
+	fn test_multicast_mac() {
+		let v4 = |s: &str| multicast_mac(parse_ip(s).unwrap()).map(|m| m.to_string());
+		assert_eq!(v4("224.0.0.1").as_deref(), Some("01:00:5e:00:00:01"));
+		assert_eq!(v4("239.255.255.250").as_deref(), Some("01:00:5e:7f:ff:fa"));
+		// The high bit of the second octet is dropped: 32:1 ambiguity.
+		assert_eq!(v4("225.128.0.1"), v4("224.0.0.1"));
+		assert_eq!(v4("ff05::1:3").as_deref(), Some("33:33:00:01:00:03"));
+		assert_eq!(v4("192.168.1.1"), None);
+		assert_eq!(v4("fe80::1"), None);
+
+		let group = Ipv4Addr::new(239, 1, 2, 3);
+		let aliases = ipv4_multicast_aliases(group).unwrap();
+		assert!(aliases.contains(&group));
+		let mac = multicast_mac(IpAddr::V4(group));
+		for alias in aliases {
+			assert_eq!(classify_ip(IpAddr::V4(alias)), IpClass::Multicast);
+			assert_eq!(multicast_mac(IpAddr::V4(alias)), mac);
+		}
+		assert!(ipv4_multicast_aliases(Ipv4Addr::new(10, 0, 0, 1)).is_none());
+	}
+
+	#[test]
+	fn test_solicited_node() {
+		let ip: Ipv6Addr = "fe80::21a:2bff:fe3c:4d5e".parse().unwrap();
+		let group = solicited_node(ip).unwrap();
+		assert_eq!(group.to_string(), "ff02::1:ff3c:4d5e");
+		assert!(is_solicited_node(group));
+		assert_eq!(classify_ip(IpAddr::V6(group)), IpClass::Multicast);
+		assert_eq!(
+			multicast_mac(IpAddr::V6(group)).unwrap().to_string(),
+			"33:33:ff:3c:4d:5e"
+		);
+		assert!(!is_solicited_node("ff02::1".parse().unwrap()));
+		assert!(solicited_node("ff02::1".parse().unwrap()).is_none());
+		assert!(solicited_node(Ipv6Addr::UNSPECIFIED).is_none());
+	}
+
+	#[test]
+	fn test_ip_class_set() {
+		let mut set = IpClassSet::new();
+		assert!(set.is_empty());
+		set.insert(IpClass::Private);
+		set |= IpClassSet::from(IpClass::Loopback);
+		assert_eq!(set.len(), 2);
+		assert!(set.contains(IpClass::Loopback));
+		assert!(set.is_subset(IpClassSet::from(IpClass::ALL)));
+		assert!(set.intersects(IpClassSet::from([IpClass::Private, IpClass::Global])));
+		set.remove(IpClass::Private);
+		assert_eq!(set, IpClassSet::from(IpClass::Loopback));
+		assert_eq!(format!("{set:?}"), "{Loopback}");
+		assert_eq!(
+			(IpClassSet::from(IpClass::ALL) - IpClassSet::from(IpClass::Global)).len(),
+			IpClass::ALL.len() - 1
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn test_ip_class_set_serde() {
+		let set: IpClassSet = serde_json::from_str(r#"["private", "loopback"]"#).unwrap();
+		assert_eq!(set, IpClassSet::from([IpClass::Private, IpClass::Loopback]));
+		assert_eq!(
+			serde_json::to_string(&set).unwrap(),
+			r#"["loopback","private"]"#
+		);
+	}
+
+	#[test]
+	fn test_embedded_ipv4_nat64_local_use() {
+		let prefix: Nat64Prefix = "64:ff9b:1::/48".parse().unwrap();
+		let v4 = Ipv4Addr::new(10, 1, 2, 3);
+		let v6 = prefix.embed(v4);
+		assert_eq!(v6.to_string(), "64:ff9b:1:a01:2:300::");
+		assert_eq!(embedded_ipv4(v6), Some(v4));
+		assert_eq!(
+			classify_ip_all(IpAddr::V6(v6)),
+			IpClassSet::from([IpClass::Global, IpClass::Private])
+		);
+	}
+
+	#[test]
+	fn test_classify_ip_all() {
+		let all = |s: &str| classify_ip_all(parse_ip(s).unwrap());
+
+		// Plain addresses carry exactly their classify_ip class.
+		assert_eq!(all("8.8.8.8"), IpClassSet::from(IpClass::Global));
+		assert_eq!(all("10.1.2.3"), IpClassSet::from(IpClass::Private));
+
+		// Embedded IPv4 adds the embedded address's classes.
+		assert_eq!(
+			all("::ffff:192.168.1.1"),
+			IpClassSet::from([IpClass::Global, IpClass::Private])
+		);
+		assert!(all("64:ff9b::10.0.0.1").contains(IpClass::Private));
+		assert!(all("64:ff9b:1:7f00:0:100::").contains(IpClass::Loopback));
+		assert!(all("2002:c000:0201::1").contains(IpClass::Documentation));
+
+		// IETF protocol assignments, some of them globally reachable anycast.
+		assert_eq!(
+			all("192.0.0.9"),
+			IpClassSet::from([IpClass::Global, IpClass::ProtocolAssignment])
+		);
+		assert_eq!(all("192.0.0.10").len(), 2);
+		assert_eq!(
+			all("192.0.0.1"),
+			IpClassSet::from(IpClass::ProtocolAssignment)
+		);
+
+		// Multicast scopes.
+		assert!(all("224.0.0.251").contains(IpClass::LinkLocal));
+		assert!(!all("239.1.1.1").contains(IpClass::LinkLocal));
+		assert!(all("ff01::1").contains(IpClass::Loopback));
+		assert!(all("ff05::2").contains(IpClass::Private));
+		assert!(all("ff0e::1").contains(IpClass::Global));
+
+		for s in [
+			"127.0.0.1",
+			"::",
+			"fe80::1",
+			"255.255.255.255",
+			"100.64.0.1",
+			"ff02::1",
+		] {
+			let ip = parse_ip(s).unwrap();
+			assert!(classify_ip_all(ip).contains(classify_ip(ip)), "{s}");
+		}
+	}
+
+	#[test]
+	fn test_class_prefixes() {
+		for &class in IpClass::ALL {
+			for net in class_prefixes(class) {
+				assert_eq!(classify_ip(net.network()), class, "{net}");
+				assert_eq!(classify_ip(net.last()), class, "{net}");
+			}
+		}
+	}
+
 	#[test]
 	fn test_is_valid_ip_literal() {
 		assert!(is_valid_ip_literal("127.0.0.1"));
@@ -293,4 +1286,79 @@ mod tests {
 		assert!(!is_valid_ip_literal("256.0.0.1"));
 		assert!(!is_valid_ip_literal(""));
 	}
+
+	#[test]
+	fn test_arithmetic() {
+		let ip = |s: &str| parse_ip(s).unwrap();
+		assert_eq!(checked_add(ip("10.0.0.255"), 1), Some(ip("10.0.1.0")));
+		assert_eq!(checked_add(ip("255.255.255.255"), 1), None);
+		assert_eq!(checked_add(ip("0.0.0.0"), 1 << 32), None);
+		assert_eq!(
+			checked_add(ip("::"), u128::MAX),
+			Some(ip("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"))
+		);
+		assert_eq!(checked_add(ip("::1"), u128::MAX), None);
+		assert_eq!(checked_sub(ip("10.0.0.0"), 1), Some(ip("9.255.255.255")));
+		assert_eq!(checked_sub(ip("0.0.0.0"), 1), None);
+		assert_eq!(
+			saturating_add(ip("255.255.255.0"), 1 << 40),
+			ip("255.255.255.255")
+		);
+		assert_eq!(
+			saturating_add(ip("::1"), u128::MAX),
+			ip("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff")
+		);
+		assert_eq!(saturating_sub(ip("::5"), 10), ip("::"));
+		assert_eq!(next(ip("::ffff")), Some(ip("::1:0")));
+		assert_eq!(prev(ip("::")), None);
+		assert_eq!(
+			distance(ip("::"), ip("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff")),
+			Some(u128::MAX)
+		);
+		assert_eq!(distance(ip("10.0.0.1"), ip("::ffff:10.0.0.1")), None);
+	}
+
+	#[test]
+	fn test_bitwise() {
+		let ip = |s: &str| parse_ip(s).unwrap();
+		assert_eq!(
+			bitand(ip("2001:db8::abcd"), ip("ffff:ffff::")),
+			Some(ip("2001:db8::"))
+		);
+		assert_eq!(
+			bitor(ip("10.1.2.3"), ip("0.0.255.255")),
+			Some(ip("10.1.255.255"))
+		);
+		assert_eq!(bitand(ip("10.1.2.3"), ip("ffff::")), None);
+		assert_eq!(bitor(ip("::1"), ip("255.0.0.0")), None);
+		assert_eq!(bitnot(ip("0.0.0.0")), ip("255.255.255.255"));
+		assert_eq!(
+			bitnot(ip("ffff:ffff:ffff:ffff::")),
+			ip("::ffff:ffff:ffff:ffff")
+		);
+	}
+
+	#[test]
+	fn test_cmp_ip() {
+		use core::cmp::Ordering;
+		let ip = |s: &str| parse_ip(s).unwrap();
+		assert_eq!(cmp_ip(ip("10.0.0.1"), ip("10.0.0.1")), Ordering::Equal);
+		assert_eq!(
+			cmp_ip(ip("10.0.0.1"), ip("::ffff:10.0.0.1")),
+			Ordering::Less
+		);
+		assert_eq!(
+			cmp_ip(ip("::ffff:10.0.0.1"), ip("10.0.0.2")),
+			Ordering::Less
+		);
+		assert_eq!(
+			cmp_ip(ip("::ffff:255.255.255.255"), ip("::")),
+			Ordering::Less
+		);
+		assert_eq!(cmp_ip(ip("255.255.255.255"), ip("::1")), Ordering::Less);
+		assert_eq!(
+			cmp_ip(ip("2001:db8::2"), ip("2001:db8::1")),
+			Ordering::Greater
+		);
+	}
 }
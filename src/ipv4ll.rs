@@ -0,0 +1,240 @@
+/* src/ipv4ll.rs */
+
+use crate::mac::MacAddr;
+use core::iter::FusedIterator;
+use core::net::Ipv4Addr;
+use core::time::Duration;
+
+/// Initial random delay before the first probe may be up to this long.
+pub const PROBE_WAIT: Duration = Duration::from_secs(1);
+/// Number of ARP probes sent for a candidate.
+pub const PROBE_NUM: u32 = 3;
+/// Minimum delay between probes.
+pub const PROBE_MIN: Duration = Duration::from_secs(1);
+/// Maximum delay between probes.
+pub const PROBE_MAX: Duration = Duration::from_secs(2);
+/// Delay before announcing a claimed address.
+pub const ANNOUNCE_WAIT: Duration = Duration::from_secs(2);
+/// Number of ARP announcements sent after claiming.
+pub const ANNOUNCE_NUM: u32 = 2;
+/// Time between announcements.
+pub const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(2);
+/// Conflicts after which new candidates are rate-limited.
+pub const MAX_CONFLICTS: u32 = 10;
+/// Minimum time between candidates once rate-limited.
+pub const RATE_LIMIT_INTERVAL: Duration = Duration::from_secs(60);
+/// Minimum interval between defensive ARPs.
+pub const DEFEND_INTERVAL: Duration = Duration::from_secs(10);
+
+/// First usable IPv4 link-local address, `169.254.1.0`.
+const FIRST: u32 = 0xa9fe_0100;
+/// Number of usable addresses, `169.254.1.0` to `169.254.254.255`.
+const COUNT: u64 = 254 * 256;
+
+/// Checks whether `ip` may be claimed by RFC 3927 autoconfiguration.
+///
+/// `169.254.0.0/24` and `169.254.255.0/24` are reserved, so only
+/// `169.254.1.0` to `169.254.254.255` qualify.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ipv4ll::is_candidate;
+///
+/// assert!(is_candidate("169.254.1.0".parse().unwrap()));
+/// assert!(!is_candidate("169.254.0.7".parse().unwrap()));
+/// assert!(!is_candidate("169.254.255.1".parse().unwrap()));
+/// ```
+#[must_use]
+pub fn is_candidate(ip: Ipv4Addr) -> bool {
+	(FIRST..FIRST + COUNT as u32).contains(&u32::from(ip))
+}
+
+/// Endless, deterministic sequence of candidate addresses for a MAC.
+///
+/// Each candidate is `169.254.1.0 + (x % 65024)`, where `x` is the next output
+/// of the SplitMix64 generator seeded with the MAC read as a big-endian
+/// 48-bit integer. The same MAC always yields the same sequence (RFC 3927
+/// section 2.1), so other implementations can reproduce it.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ipv4ll::candidates;
+/// use netsem::{IpClass, MacAddr, classify_ip};
+///
+/// let mac: MacAddr = "00:1a:2b:3c:4d:5e".parse().unwrap();
+/// let first: Vec<_> = candidates(mac).take(3).collect();
+/// assert_eq!(first, candidates(mac).take(3).collect::<Vec<_>>());
+/// assert!(first.iter().all(|ip| classify_ip((*ip).into()) == IpClass::LinkLocal));
+/// ```
+#[must_use]
+pub fn candidates(mac: MacAddr) -> Candidates {
+	let mut seed = [0u8; 8];
+	seed[2..].copy_from_slice(&mac.octets());
+	Candidates {
+		state: u64::from_be_bytes(seed),
+	}
+}
+
+/// Iterator returned by [`candidates`].
+#[derive(Debug, Clone)]
+pub struct Candidates {
+	state: u64,
+}
+
+impl Iterator for Candidates {
+	type Item = Ipv4Addr;
+
+	fn next(&mut self) -> Option<Ipv4Addr> {
+		// splitmix64
+		self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^= z >> 31;
+		Some(Ipv4Addr::from(FIRST + (z % COUNT) as u32))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(usize::MAX, None)
+	}
+}
+
+impl FusedIterator for Candidates {}
+
+/// Tracks the address being claimed and how to react to conflicts.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::MacAddr;
+/// use netsem::ipv4ll::{Claim, MAX_CONFLICTS, RATE_LIMIT_INTERVAL};
+/// use std::time::Duration;
+///
+/// let mut claim = Claim::new("00:1a:2b:3c:4d:5e".parse().unwrap());
+/// let first = claim.address();
+///
+/// let (next, delay) = claim.conflict();
+/// assert_ne!(next, first);
+/// assert_eq!(delay, Duration::ZERO);
+///
+/// for _ in 1..MAX_CONFLICTS - 1 {
+///     claim.conflict();
+/// }
+/// assert_eq!(claim.conflict().1, RATE_LIMIT_INTERVAL);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Claim {
+	candidates: Candidates,
+	address: Ipv4Addr,
+	conflicts: u32,
+}
+
+impl Claim {
+	/// Starts claiming the first candidate for `mac`.
+	#[must_use]
+	pub fn new(mac: MacAddr) -> Self {
+		let mut candidates = candidates(mac);
+		let address = candidates.next().expect("candidates never end");
+		Self {
+			candidates,
+			address,
+			conflicts: 0,
+		}
+	}
+
+	/// Returns the address currently being probed or held.
+	#[must_use]
+	pub fn address(&self) -> Ipv4Addr {
+		self.address
+	}
+
+	/// Returns the number of conflicts seen so far.
+	#[must_use]
+	pub fn conflicts(&self) -> u32 {
+		self.conflicts
+	}
+
+	/// Records a conflict on the current address and moves to the next candidate.
+	///
+	/// Candidates equal to the conflicting address are skipped. Returns the new candidate and how long to wait before probing it:
+	/// zero until [`MAX_CONFLICTS`] conflicts have occurred, then
+	/// [`RATE_LIMIT_INTERVAL`] (RFC 3927 section 2.2.1).
+	pub fn conflict(&mut self) -> (Ipv4Addr, Duration) {
+		self.conflicts = self.conflicts.saturating_add(1);
+		let conflicted = self.address;
+		self.address = self
+			.candidates
+			.find(|&candidate| candidate != conflicted)
+			.expect("candidates never end");
+		let delay = if self.conflicts >= MAX_CONFLICTS {
+			RATE_LIMIT_INTERVAL
+		} else {
+			Duration::ZERO
+		};
+		(self.address, delay)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ip::{IpClass, classify_ip};
+	use alloc::vec::Vec;
+
+	#[test]
+	fn test_candidates() {
+		let mac = MacAddr::new([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+		let seq: Vec<Ipv4Addr> = candidates(mac).take(1000).collect();
+		for ip in &seq {
+			assert!(is_candidate(*ip), "{ip}");
+			assert_eq!(classify_ip((*ip).into()), IpClass::LinkLocal);
+		}
+		assert_eq!(seq, candidates(mac).take(1000).collect::<Vec<_>>());
+
+		let other = MacAddr::new([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5f]);
+		assert_ne!(candidates(other).next(), seq.first().copied());
+
+		// Pin the sequence so changes to the generator are caught.
+		let zero: Vec<Ipv4Addr> = candidates(MacAddr::new([0; 6])).take(2).collect();
+		assert_eq!(zero, PINNED);
+	}
+
+	const PINNED: [Ipv4Addr; 2] = [
+		Ipv4Addr::new(169, 254, 84, 175),
+		Ipv4Addr::new(169, 254, 132, 244),
+	];
+
+	#[test]
+	fn test_is_candidate_bounds() {
+		assert!(is_candidate(Ipv4Addr::new(169, 254, 254, 255)));
+		assert!(!is_candidate(Ipv4Addr::new(169, 254, 0, 255)));
+		assert!(!is_candidate(Ipv4Addr::new(169, 253, 1, 0)));
+	}
+
+	#[test]
+	fn test_claim_skips_conflicting_address() {
+		let mut claim = Claim::new(MacAddr::new([2, 0, 0, 0, 0, 1]));
+		// Make the upcoming candidate repeat the address that conflicts.
+		let mut upcoming = claim.candidates.clone();
+		let repeat = upcoming.next().unwrap();
+		let after = upcoming.next().unwrap();
+		claim.address = repeat;
+		let (next, _) = claim.conflict();
+		assert_ne!(next, repeat);
+		assert_eq!(next, after);
+	}
+
+	#[test]
+	fn test_claim_rate_limit() {
+		let mut claim = Claim::new(MacAddr::new([2, 0, 0, 0, 0, 1]));
+		let mut delays = Vec::new();
+		for _ in 0..12 {
+			delays.push(claim.conflict().1);
+		}
+		assert_eq!(claim.conflicts(), 12);
+		assert!(delays[..9].iter().all(|d| d.is_zero()));
+		assert!(delays[9..].iter().all(|d| *d == RATE_LIMIT_INTERVAL));
+	}
+}
@@ -1,5 +1,6 @@
 /* src/lib.rs */
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
 
@@ -10,22 +11,88 @@
 //!
 //! It offers a strict separation between logic/validation and OS-level operations.
 //! OS-level checks (binding, connecting) are available only via the `check` feature.
+//!
+//! The crate is `no_std` + `alloc` when the default `std` feature is disabled:
+//! parsing, classification, prefixes and ranges work on `core::net` types,
+//! while OS checks and file loading require `std`.
+
+extern crate alloc;
 
+/// IP address truncation, redaction, and keyed pseudonymization for logs.
+pub mod anon;
+/// Autonomous System Number parsing and classification.
+pub mod asn;
+/// Cloud provider IP range feed parsing.
+#[cfg(feature = "cloud")]
+pub mod cloud;
 /// Error types and handling.
 pub mod error;
+/// IPv6 interface identifiers: reserved IIDs, stable-privacy (RFC 7217) and temporary (RFC 8981) addresses.
+pub mod iid;
 /// IP address parsing and classification.
 pub mod ip;
+/// IPv4 link-local (RFC 3927) candidate generation and conflict handling.
+pub mod ipv4ll;
+/// Hardware (EUI-48 / EUI-64) addresses and IPv6 interface identifiers.
+pub mod mac;
+/// NAT64 prefixes and DNS64 address synthesis (RFC 6052, RFC 6147).
+pub mod nat64;
+/// IP prefix (CIDR) parsing, containment, and longest-prefix-match tables.
+pub mod net;
+/// IPv6 address planning: nibble-aligned allocation and numbering schemes.
+pub mod plan;
 /// Port validation, classification, and OS-level checks.
 pub mod port;
+/// Nmap-style port lists and per-protocol port sets.
+pub mod port_set;
+/// IP protocol numbers (IANA registry) and their names.
+pub mod proto;
+/// IP address ranges and lazy address iteration.
+pub mod range;
+/// RIR delegated-statistics parsing and attribution lookups.
+pub mod rir;
+/// RPKI route origin validation (RFC 6811).
+#[cfg(feature = "rpki")]
+pub mod rpki;
+/// Random address and port sampling for load tests.
+#[cfg(feature = "rand")]
+pub mod sample;
+/// Serde helpers for fields stored in their string forms.
+#[cfg(feature = "serde")]
+pub mod serde_str;
+/// IANA service names and `/etc/services` parsing.
+pub mod services;
 /// Socket address validation and binding checks.
 pub mod socket;
+/// Proptest strategies producing addresses and ports of a given kind.
+#[cfg(feature = "proptest")]
+pub mod strategy;
+/// Covering supernets, lossless aggregation, and lossy route summarization.
+pub mod summary;
+/// Unique Local Address (RFC 4193) prefix generation and checks.
+pub mod ula;
 
 // Re-export core types for convenience
+pub use asn::{Asn, AsnClass, asn_class_ranges, classify_asn};
 pub use error::NetSemError;
-pub use ip::{IpClass, classify_ip, is_valid_ip_literal, parse_ip};
-pub use port::{PortClass, classify_port, validate_port, validate_port_or_zero};
+pub use ip::{
+	IpClass, IpClassSet, class_prefixes, classify_ip, classify_ip_all, embedded_ipv4,
+	ipv4_multicast_aliases, is_solicited_node, is_valid_ip_literal, multicast_mac, parse_ip,
+	solicited_node,
+};
+pub use mac::{Eui64, MacAddr, embedded_mac};
+pub use nat64::Nat64Prefix;
+pub use net::{IpNet, PrefixTable};
+pub use port::{
+	AnyPort, EphemeralPort, Port, PortClass, PortRange, PrivilegedPort, TransportPort, classify_port,
+	parse_port, parse_port_spec, port_class_range, validate_port, validate_port_or_zero,
+};
+pub use port_set::PortSet;
+pub use proto::Protocol;
+pub use range::{IpAddrIter, IpRange};
+pub use summary::{Summary, aggregate, common_supernet, summarize};
 
 #[cfg(feature = "check")]
-pub use port::{check_bind_tcp, check_bind_udp, check_connect_tcp, check_connect_udp};
+pub use port::{check_bind, check_bind_tcp, check_bind_udp, check_connect_tcp, check_connect_udp};
 
-pub use socket::validate_socket_addr;
+pub use socket::{TransportAddr, validate_socket_addr};
@@ -0,0 +1,365 @@
+/* src/mac.rs */
+
+use crate::error::NetSemError;
+use crate::net::IpNet;
+use alloc::borrow::ToOwned;
+use alloc::format;
+use core::fmt;
+use core::net::{IpAddr, Ipv6Addr};
+use core::str::FromStr;
+
+/// An EUI-48 hardware (MAC) address.
+///
+/// Parses colon (`00:1a:2b:3c:4d:5e`), dash (`00-1A-2B-3C-4D-5E`) and dot
+/// (`001a.2b3c.4d5e`) notations; displays as lowercase colon-separated hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MacAddr([u8; 6]);
+
+impl MacAddr {
+	/// The broadcast address `ff:ff:ff:ff:ff:ff`.
+	pub const BROADCAST: Self = Self([0xff; 6]);
+
+	/// Creates an address from its six octets.
+	#[must_use]
+	pub const fn new(octets: [u8; 6]) -> Self {
+		Self(octets)
+	}
+
+	/// Returns the six octets of the address.
+	#[must_use]
+	pub const fn octets(&self) -> [u8; 6] {
+		self.0
+	}
+
+	/// Returns `true` if the I/G bit is clear (individual address).
+	#[must_use]
+	pub fn is_unicast(&self) -> bool {
+		self.0[0] & 0x01 == 0
+	}
+
+	/// Returns `true` if the I/G bit is set (group address, including broadcast).
+	#[must_use]
+	pub fn is_multicast(&self) -> bool {
+		!self.is_unicast()
+	}
+
+	/// Returns `true` for `ff:ff:ff:ff:ff:ff`.
+	#[must_use]
+	pub fn is_broadcast(&self) -> bool {
+		*self == Self::BROADCAST
+	}
+
+	/// Returns `true` if the U/L bit is clear (universally administered, OUI-assigned).
+	#[must_use]
+	pub fn is_universal(&self) -> bool {
+		self.0[0] & 0x02 == 0
+	}
+
+	/// Returns `true` if the U/L bit is set (locally administered).
+	#[must_use]
+	pub fn is_local(&self) -> bool {
+		!self.is_universal()
+	}
+
+	/// Expands the address to EUI-64 by inserting `ff:fe` in the middle.
+	#[must_use]
+	pub fn to_eui64(&self) -> Eui64 {
+		let [a, b, c, d, e, f] = self.0;
+		Eui64([a, b, c, 0xff, 0xfe, d, e, f])
+	}
+
+	/// Returns the modified EUI-64 IPv6 interface identifier (RFC 4291 Appendix A).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::MacAddr;
+	///
+	/// let mac: MacAddr = "00:1a:2b:3c:4d:5e".parse().unwrap();
+	/// assert_eq!(mac.interface_id(), 0x021a_2bff_fe3c_4d5e);
+	/// ```
+	#[must_use]
+	pub fn interface_id(&self) -> u64 {
+		self.to_eui64().interface_id()
+	}
+
+	/// Returns the SLAAC link-local address `fe80::/64` + modified EUI-64.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::MacAddr;
+	///
+	/// let mac: MacAddr = "00:1a:2b:3c:4d:5e".parse().unwrap();
+	/// assert_eq!(mac.link_local().to_string(), "fe80::21a:2bff:fe3c:4d5e");
+	/// ```
+	#[must_use]
+	pub fn link_local(&self) -> Ipv6Addr {
+		Ipv6Addr::from((0xfe80_u128 << 112) | u128::from(self.interface_id()))
+	}
+
+	/// Returns the SLAAC address formed from `prefix` and the modified EUI-64.
+	///
+	/// # Returns
+	///
+	/// * `Ok(Ipv6Addr)` if `prefix` is an IPv6 `/64`.
+	/// * `Err(NetSemError::InvalidPrefix)` otherwise.
+	pub fn slaac_address(&self, prefix: IpNet) -> Result<Ipv6Addr, NetSemError> {
+		match prefix.network() {
+			IpAddr::V6(net) if prefix.prefix_len() == 64 => Ok(Ipv6Addr::from(
+				u128::from(net) | u128::from(self.interface_id()),
+			)),
+			_ => Err(NetSemError::InvalidPrefix(format!(
+				"{prefix} (expected IPv6 /64)"
+			))),
+		}
+	}
+}
+
+impl fmt::Display for MacAddr {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let [a, b, c, d, e, g] = self.0;
+		write!(f, "{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{g:02x}")
+	}
+}
+
+impl FromStr for MacAddr {
+	type Err = NetSemError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		parse_hex_groups::<6>(s)
+			.map(Self)
+			.ok_or_else(|| NetSemError::InvalidMac(s.to_owned()))
+	}
+}
+
+impl From<[u8; 6]> for MacAddr {
+	fn from(octets: [u8; 6]) -> Self {
+		Self(octets)
+	}
+}
+
+/// An EUI-64 identifier (e.g., `00:1a:2b:ff:fe:3c:4d:5e`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Eui64([u8; 8]);
+
+impl Eui64 {
+	/// Creates an identifier from its eight octets.
+	#[must_use]
+	pub const fn new(octets: [u8; 8]) -> Self {
+		Self(octets)
+	}
+
+	/// Returns the eight octets of the identifier.
+	#[must_use]
+	pub const fn octets(&self) -> [u8; 8] {
+		self.0
+	}
+
+	/// Returns the EUI-48 this identifier was expanded from, if it carries the `ff:fe` marker.
+	#[must_use]
+	pub fn to_mac(&self) -> Option<MacAddr> {
+		let [a, b, c, m1, m2, d, e, f] = self.0;
+		(m1 == 0xff && m2 == 0xfe).then_some(MacAddr([a, b, c, d, e, f]))
+	}
+
+	/// Returns the modified EUI-64 interface identifier (U/L bit inverted).
+	#[must_use]
+	pub fn interface_id(&self) -> u64 {
+		u64::from_be_bytes(self.0) ^ (0x02 << 56)
+	}
+
+	/// Recovers an EUI-64 from a modified EUI-64 interface identifier.
+	#[must_use]
+	pub fn from_interface_id(iid: u64) -> Self {
+		Self((iid ^ (0x02 << 56)).to_be_bytes())
+	}
+}
+
+impl fmt::Display for Eui64 {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, b) in self.0.iter().enumerate() {
+			if i > 0 {
+				f.write_str(":")?;
+			}
+			write!(f, "{b:02x}")?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for Eui64 {
+	type Err = NetSemError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		parse_hex_groups::<8>(s)
+			.map(Self)
+			.ok_or_else(|| NetSemError::InvalidMac(s.to_owned()))
+	}
+}
+
+/// Returns the MAC address embedded in a modified EUI-64 IPv6 interface identifier.
+///
+/// SLAAC addresses built from a MAC carry `ff:fe` in the middle of the
+/// interface identifier, which exposes the hardware address (and its vendor)
+/// to every peer. Use this to flag such addresses in privacy audits.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::embedded_mac;
+///
+/// let leaky = "2001:db8::21a:2bff:fe3c:4d5e".parse().unwrap();
+/// assert_eq!(embedded_mac(leaky).unwrap().to_string(), "00:1a:2b:3c:4d:5e");
+///
+/// let opaque = "2001:db8::8c3a:91e2:7f10:4b2c".parse().unwrap();
+/// assert!(embedded_mac(opaque).is_none());
+/// ```
+#[must_use]
+pub fn embedded_mac(ip: Ipv6Addr) -> Option<MacAddr> {
+	Eui64::from_interface_id(u128::from(ip) as u64).to_mac()
+}
+
+/// Parses `N` hex octets in colon, dash or dot (`xxxx.xxxx.xxxx`) notation.
+fn parse_hex_groups<const N: usize>(s: &str) -> Option<[u8; N]> {
+	let mut out = [0u8; N];
+	if s.contains('.') {
+		// Dot notation: groups of four hex digits.
+		let mut digits = 0;
+		for group in s.split('.') {
+			if group.len() != 4 || !group.is_ascii() || digits + 2 > N {
+				return None;
+			}
+			out[digits] = parse_octet(&group[..2])?;
+			out[digits + 1] = parse_octet(&group[2..])?;
+			digits += 2;
+		}
+		return (digits == N).then_some(out);
+	}
+	let sep = if s.contains('-') { '-' } else { ':' };
+	let mut groups = s.split(sep);
+	for slot in &mut out {
+		let group = groups.next()?;
+		if group.len() != 2 {
+			return None;
+		}
+		*slot = parse_octet(group)?;
+	}
+	groups.next().is_none().then_some(out)
+}
+
+fn parse_octet(s: &str) -> Option<u8> {
+	if !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+		return None;
+	}
+	u8::from_str_radix(s, 16).ok()
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for MacAddr {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MacAddr {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+		s.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for MacAddr {
+	fn schema_name() -> alloc::borrow::Cow<'static, str> {
+		"MacAddr".into()
+	}
+
+	fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "EUI-48 MAC address, e.g. 00:1a:2b:3c:4d:5e."
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn mac(s: &str) -> MacAddr {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn test_parse_mac() {
+		let expected = MacAddr::new([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+		assert_eq!(mac("00:1a:2b:3c:4d:5e"), expected);
+		assert_eq!(mac("00-1A-2B-3C-4D-5E"), expected);
+		assert_eq!(mac("001a.2b3c.4d5e"), expected);
+		assert_eq!(expected.to_string(), "00:1a:2b:3c:4d:5e");
+
+		for bad in [
+			"",
+			"00:1a:2b:3c:4d",
+			"00:1a:2b:3c:4d:5e:6f",
+			"0:1a:2b:3c:4d:5e",
+			"00:1a-2b:3c:4d:5e",
+			"00:1a:2b:3c:4d:+e",
+			"001a.2b3c",
+			"001a.2b3c.4d5e.6f70",
+			"0\u{e9}1.2b3c.4d5e",
+		] {
+			assert!(
+				matches!(bad.parse::<MacAddr>(), Err(NetSemError::InvalidMac(_))),
+				"{bad}"
+			);
+		}
+	}
+
+	#[test]
+	fn test_mac_bits() {
+		let universal = mac("00:1a:2b:3c:4d:5e");
+		assert!(universal.is_unicast() && universal.is_universal());
+		assert!(!universal.is_broadcast());
+
+		let local = mac("02:00:00:00:00:01");
+		assert!(local.is_local() && local.is_unicast());
+
+		let multicast = mac("01:00:5e:00:00:fb");
+		assert!(multicast.is_multicast() && !multicast.is_broadcast());
+
+		assert!(MacAddr::BROADCAST.is_broadcast() && MacAddr::BROADCAST.is_multicast());
+	}
+
+	#[test]
+	fn test_eui64() {
+		let eui = mac("00:1a:2b:3c:4d:5e").to_eui64();
+		assert_eq!(eui.to_string(), "00:1a:2b:ff:fe:3c:4d:5e");
+		assert_eq!(eui.to_mac(), Some(mac("00:1a:2b:3c:4d:5e")));
+		assert_eq!(Eui64::from_interface_id(eui.interface_id()), eui);
+		assert_eq!("00-1a-2b-ff-fe-3c-4d-5e".parse::<Eui64>().unwrap(), eui);
+		assert_eq!(
+			"00:11:22:33:44:55:66:77".parse::<Eui64>().unwrap().to_mac(),
+			None
+		);
+	}
+
+	#[test]
+	fn test_slaac_address() {
+		let m = mac("02:00:5e:10:00:01");
+		// Locally administered bit flips to 0 in the modified EUI-64.
+		assert_eq!(m.link_local().to_string(), "fe80::5eff:fe10:1");
+		let prefix: IpNet = "2001:db8:1:2::/64".parse().unwrap();
+		let addr = m.slaac_address(prefix).unwrap();
+		assert_eq!(addr.to_string(), "2001:db8:1:2:0:5eff:fe10:1");
+		assert_eq!(embedded_mac(addr), Some(m));
+
+		assert!(m.slaac_address("2001:db8::/96".parse().unwrap()).is_err());
+		assert!(m.slaac_address("2001:db8::/48".parse().unwrap()).is_err());
+		assert!(m.slaac_address("10.0.0.0/8".parse().unwrap()).is_err());
+	}
+}
@@ -0,0 +1,291 @@
+/* src/nat64.rs */
+
+use crate::error::NetSemError;
+use crate::ip::{IpClass, classify_ip, classify_ip_all};
+use crate::net::IpNet;
+use alloc::format;
+use core::fmt;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
+
+/// A NAT64 prefix for embedding IPv4 addresses in IPv6 (RFC 6052).
+///
+/// Either the well-known prefix `64:ff9b::/96` or a network-specific prefix
+/// of length 32, 40, 48, 56, 64 or 96.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::nat64::Nat64Prefix;
+///
+/// let wkp = Nat64Prefix::well_known();
+/// let v6 = wkp.synthesize("192.0.2.33".parse().unwrap());
+/// assert!(v6.is_err()); // documentation addresses are not global
+///
+/// let v6 = wkp.synthesize("8.8.8.8".parse().unwrap()).unwrap();
+/// assert_eq!(v6.to_string(), "64:ff9b::808:808");
+/// assert_eq!(wkp.extract(v6).unwrap().to_string(), "8.8.8.8");
+///
+/// let nsp: Nat64Prefix = "2001:db8:122::/48".parse().unwrap();
+/// assert_eq!(nsp.embed("192.0.2.33".parse().unwrap()).to_string(), "2001:db8:122:c000:2:2100::");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Nat64Prefix(IpNet);
+
+impl Nat64Prefix {
+	/// Returns the well-known prefix `64:ff9b::/96`.
+	#[must_use]
+	pub fn well_known() -> Self {
+		Self(
+			IpNet::new(
+				IpAddr::V6(Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0, 0)),
+				96,
+			)
+			.expect("/96 is a valid IPv6 prefix length"),
+		)
+	}
+
+	/// Validates `net` as a NAT64 prefix.
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPrefix)` if `net` is not IPv6, its length is not
+	///   one of 32, 40, 48, 56, 64 or 96, or it sets bits 64-71 (the reserved "u" octet).
+	pub fn new(net: IpNet) -> Result<Self, NetSemError> {
+		let len = net.prefix_len();
+		let invalid = || {
+			NetSemError::InvalidPrefix(format!(
+				"{net} (NAT64 prefixes are IPv6 /32, /40, /48, /56, /64 or /96 with bits 64-71 zero)"
+			))
+		};
+		if !net.is_ipv6() || !matches!(len, 32 | 40 | 48 | 56 | 64 | 96) {
+			return Err(invalid());
+		}
+		let IpAddr::V6(network) = net.network() else {
+			unreachable!("checked IPv6 above")
+		};
+		if network.octets()[8] != 0 {
+			return Err(invalid());
+		}
+		Ok(Self(net))
+	}
+
+	/// Returns the prefix.
+	#[must_use]
+	pub fn net(&self) -> IpNet {
+		self.0
+	}
+
+	/// Returns `true` for the well-known prefix `64:ff9b::/96`.
+	#[must_use]
+	pub fn is_well_known(&self) -> bool {
+		*self == Self::well_known()
+	}
+
+	/// Embeds `v4` in the prefix per RFC 6052 section 2.2, without any policy checks.
+	///
+	/// For prefixes shorter than `/96`, the IPv4 octets skip the "u" octet
+	/// (bits 64-71), and the suffix is zero.
+	#[must_use]
+	pub fn embed(&self, v4: Ipv4Addr) -> Ipv6Addr {
+		let mut octets = self.network().octets();
+		for (i, octet) in v4.octets().into_iter().enumerate() {
+			octets[self.octet_index(i)] = octet;
+		}
+		Ipv6Addr::from(octets)
+	}
+
+	/// Embeds `v4` unless DNS64 must not synthesize an address for it.
+	///
+	/// The well-known prefix only represents global IPv4 addresses (RFC 6052
+	/// section 3.1), so any address [`classify_ip_all`] does not label
+	/// [`IpClass::Global`] is refused; the globally reachable `192.0.0.9` and
+	/// `192.0.0.10` are accepted. A network-specific prefix may also carry
+	/// private, shared, documentation and benchmarking space, but loopback,
+	/// unspecified, link-local, multicast and broadcast addresses are always
+	/// refused (RFC 6147 section 5.1.4 exclusions).
+	///
+	/// # Returns
+	///
+	/// * `Ok(Ipv6Addr)` with the synthesized address.
+	/// * `Err(NetSemError::Nat64Excluded)` if the address is excluded.
+	pub fn synthesize(&self, v4: Ipv4Addr) -> Result<Ipv6Addr, NetSemError> {
+		let class = classify_ip(IpAddr::V4(v4));
+		let excluded = if self.is_well_known() {
+			!classify_ip_all(IpAddr::V4(v4)).contains(IpClass::Global)
+		} else {
+			matches!(
+				class,
+				IpClass::Loopback
+					| IpClass::Unspecified
+					| IpClass::LinkLocal
+					| IpClass::Multicast
+					| IpClass::Broadcast
+			)
+		};
+		if excluded {
+			return Err(NetSemError::Nat64Excluded { addr: v4, class });
+		}
+		Ok(self.embed(v4))
+	}
+
+	/// Recovers the IPv4 address embedded in `v6`.
+	///
+	/// Returns `None` if `v6` is not inside the prefix.
+	#[must_use]
+	pub fn extract(&self, v6: Ipv6Addr) -> Option<Ipv4Addr> {
+		if !self.0.contains(IpAddr::V6(v6)) {
+			return None;
+		}
+		let octets = v6.octets();
+		Some(Ipv4Addr::from(core::array::from_fn::<u8, 4, _>(|i| {
+			octets[self.octet_index(i)]
+		})))
+	}
+
+	fn network(&self) -> Ipv6Addr {
+		match self.0.network() {
+			IpAddr::V6(v6) => v6,
+			IpAddr::V4(_) => unreachable!("NAT64 prefixes are IPv6"),
+		}
+	}
+
+	/// Position of IPv4 octet `i` within the IPv6 address, skipping octet 8.
+	fn octet_index(&self, i: usize) -> usize {
+		let index = usize::from(self.0.prefix_len() / 8) + i;
+		if index >= 8 && self.0.prefix_len() < 96 {
+			index + 1
+		} else {
+			index
+		}
+	}
+}
+
+impl Default for Nat64Prefix {
+	fn default() -> Self {
+		Self::well_known()
+	}
+}
+
+impl TryFrom<IpNet> for Nat64Prefix {
+	type Error = NetSemError;
+
+	fn try_from(net: IpNet) -> Result<Self, Self::Error> {
+		Self::new(net)
+	}
+}
+
+impl fmt::Display for Nat64Prefix {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.0, f)
+	}
+}
+
+impl FromStr for Nat64Prefix {
+	type Err = NetSemError;
+
+	/// Parses a prefix in CIDR notation and validates it with [`Nat64Prefix::new`].
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::new(s.parse()?)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Nat64Prefix {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Nat64Prefix {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+		s.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Nat64Prefix {
+	fn schema_name() -> alloc::borrow::Cow<'static, str> {
+		"Nat64Prefix".into()
+	}
+
+	fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "NAT64 IPv6 prefix of length 32, 40, 48, 56, 64 or 96, e.g. 64:ff9b::/96."
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_rfc6052_examples() {
+		// RFC 6052 section 2.4, IPv4 address 192.0.2.33.
+		let v4: Ipv4Addr = "192.0.2.33".parse().unwrap();
+		for (prefix, expected) in [
+			("2001:db8::/32", "2001:db8:c000:221::"),
+			("2001:db8:100::/40", "2001:db8:1c0:2:21::"),
+			("2001:db8:122::/48", "2001:db8:122:c000:2:2100::"),
+			("2001:db8:122:300::/56", "2001:db8:122:3c0:0:221::"),
+			("2001:db8:122:344::/64", "2001:db8:122:344:c0:2:2100:0"),
+			("2001:db8:122:344::/96", "2001:db8:122:344::192.0.2.33"),
+			("64:ff9b::/96", "64:ff9b::192.0.2.33"),
+		] {
+			let prefix: Nat64Prefix = prefix.parse().unwrap();
+			let v6 = prefix.embed(v4);
+			assert_eq!(v6, expected.parse::<Ipv6Addr>().unwrap(), "{prefix}");
+			assert_eq!(prefix.extract(v6), Some(v4), "{prefix}");
+		}
+	}
+
+	#[test]
+	fn test_prefix_validation() {
+		assert!("2001:db8::/33".parse::<Nat64Prefix>().is_err());
+		assert!("10.0.0.0/8".parse::<Nat64Prefix>().is_err());
+		assert!("2001:db8:0:0:100::/96".parse::<Nat64Prefix>().is_err());
+		assert!("2001:db8:0:0:0:100::/96".parse::<Nat64Prefix>().is_ok());
+		assert!(Nat64Prefix::default().is_well_known());
+		assert!(
+			!"2001:db8::/96"
+				.parse::<Nat64Prefix>()
+				.unwrap()
+				.is_well_known()
+		);
+	}
+
+	#[test]
+	fn test_synthesis_exclusions() {
+		let wkp = Nat64Prefix::well_known();
+		let nsp: Nat64Prefix = "2001:db8:64::/96".parse().unwrap();
+		for (ip, wkp_ok, nsp_ok) in [
+			("1.1.1.1", true, true),
+			("10.1.2.3", false, true),
+			("192.0.0.8", false, true),
+			("192.0.0.9", true, true),
+			("192.0.0.10", true, true),
+			("100.64.0.1", false, true),
+			("127.0.0.1", false, false),
+			("0.0.0.0", false, false),
+			("169.254.1.1", false, false),
+			("224.0.0.1", false, false),
+			("255.255.255.255", false, false),
+		] {
+			let v4: Ipv4Addr = ip.parse().unwrap();
+			assert_eq!(wkp.synthesize(v4).is_ok(), wkp_ok, "{ip}");
+			assert_eq!(nsp.synthesize(v4).is_ok(), nsp_ok, "{ip}");
+		}
+		assert!(matches!(
+			wkp.synthesize("192.168.0.1".parse().unwrap()),
+			Err(NetSemError::Nat64Excluded {
+				class: IpClass::Private,
+				..
+			})
+		));
+		assert_eq!(wkp.extract("2001:db8::1".parse().unwrap()), None);
+	}
+}
@@ -0,0 +1,448 @@
+/* src/net.rs */
+
+use crate::error::NetSemError;
+use crate::ip::{bits_to_ip, ip_to_bits};
+use alloc::borrow::ToOwned;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use core::fmt;
+use core::net::IpAddr;
+use core::str::FromStr;
+
+/// An IP prefix in CIDR notation (e.g., `10.0.0.0/8`, `2001:db8::/32`).
+///
+/// The stored address is always the network address: host bits are cleared
+/// on construction, so `10.1.2.3/8` and `10.0.0.0/8` are the same prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IpNet {
+	addr: IpAddr,
+	prefix_len: u8,
+}
+
+impl IpNet {
+	/// Creates a prefix from an address and a prefix length.
+	///
+	/// # Returns
+	///
+	/// * `Ok(IpNet)` with host bits cleared.
+	/// * `Err(NetSemError::InvalidPrefix)` if `prefix_len` exceeds 32 (IPv4) or 128 (IPv6).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::IpNet;
+	///
+	/// let net = IpNet::new("10.1.2.3".parse().unwrap(), 8).unwrap();
+	/// assert_eq!(net.to_string(), "10.0.0.0/8");
+	///
+	/// assert!(IpNet::new("10.0.0.0".parse().unwrap(), 33).is_err());
+	/// ```
+	pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, NetSemError> {
+		let max = max_prefix_len(addr);
+		if prefix_len > max {
+			return Err(NetSemError::InvalidPrefix(format!("{addr}/{prefix_len}")));
+		}
+		let bits = ip_to_bits(addr) & mask_bits(prefix_len, max);
+		Ok(Self {
+			addr: bits_to_ip(bits, addr.is_ipv4()),
+			prefix_len,
+		})
+	}
+
+	/// Returns the network address of the prefix.
+	#[must_use]
+	pub fn network(&self) -> IpAddr {
+		self.addr
+	}
+
+	/// Returns the last address covered by the prefix.
+	///
+	/// For IPv4 this is the broadcast address of the subnet.
+	#[must_use]
+	pub fn last(&self) -> IpAddr {
+		bits_to_ip(
+			ip_to_bits(self.addr) | ip_to_bits(self.hostmask()),
+			self.is_ipv4(),
+		)
+	}
+
+	/// Returns the prefix length.
+	#[must_use]
+	pub fn prefix_len(&self) -> u8 {
+		self.prefix_len
+	}
+
+	/// Returns the maximum prefix length for the address family (32 or 128).
+	#[must_use]
+	pub fn max_prefix_len(&self) -> u8 {
+		max_prefix_len(self.addr)
+	}
+
+	/// Returns the netmask as an address (e.g., `255.255.255.0` for a `/24`).
+	#[must_use]
+	pub fn netmask(&self) -> IpAddr {
+		bits_to_ip(
+			mask_bits(self.prefix_len, self.max_prefix_len()),
+			self.is_ipv4(),
+		)
+	}
+
+	/// Returns the hostmask as an address (e.g., `0.0.0.255` for a `/24`).
+	#[must_use]
+	pub fn hostmask(&self) -> IpAddr {
+		let max = self.max_prefix_len();
+		bits_to_ip(
+			!mask_bits(self.prefix_len, max) & family_bits(max),
+			self.is_ipv4(),
+		)
+	}
+
+	/// Returns `true` if this is an IPv4 prefix.
+	#[must_use]
+	pub fn is_ipv4(&self) -> bool {
+		self.addr.is_ipv4()
+	}
+
+	/// Returns `true` if this is an IPv6 prefix.
+	#[must_use]
+	pub fn is_ipv6(&self) -> bool {
+		self.addr.is_ipv6()
+	}
+
+	/// Checks whether `ip` falls inside the prefix.
+	///
+	/// Addresses of the other family are never contained.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::IpNet;
+	///
+	/// let net: IpNet = "192.168.0.0/16".parse().unwrap();
+	/// assert!(net.contains("192.168.10.1".parse().unwrap()));
+	/// assert!(!net.contains("10.0.0.1".parse().unwrap()));
+	/// ```
+	#[must_use]
+	pub fn contains(&self, ip: IpAddr) -> bool {
+		if ip.is_ipv4() != self.is_ipv4() {
+			return false;
+		}
+		ip_to_bits(ip) & mask_bits(self.prefix_len, self.max_prefix_len()) == ip_to_bits(self.addr)
+	}
+
+	/// Checks whether `other` is equal to or more specific than this prefix.
+	#[must_use]
+	pub fn contains_net(&self, other: &Self) -> bool {
+		other.prefix_len >= self.prefix_len && self.contains(other.addr)
+	}
+}
+
+impl fmt::Display for IpNet {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{}", self.addr, self.prefix_len)
+	}
+}
+
+impl FromStr for IpNet {
+	type Err = NetSemError;
+
+	/// Parses CIDR notation such as `10.0.0.0/8` or `2001:db8::/32`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || NetSemError::InvalidPrefix(s.to_owned());
+		let (addr, len) = s.split_once('/').ok_or_else(invalid)?;
+		let addr = addr.parse::<IpAddr>().map_err(|_| invalid())?;
+		if len.is_empty() || !len.bytes().all(|b| b.is_ascii_digit()) {
+			return Err(invalid());
+		}
+		let len = len.parse::<u8>().map_err(|_| invalid())?;
+		Self::new(addr, len).map_err(|_| invalid())
+	}
+}
+
+impl From<IpAddr> for IpNet {
+	/// Creates a host prefix (`/32` or `/128`) covering only `ip`.
+	fn from(ip: IpAddr) -> Self {
+		Self {
+			addr: ip,
+			prefix_len: max_prefix_len(ip),
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for IpNet {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for IpNet {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+		s.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for IpNet {
+	fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+		let addr = <IpAddr as arbitrary::Arbitrary<'a>>::arbitrary(u)?;
+		let len = u.int_in_range(0..=max_prefix_len(addr))?;
+		Self::new(addr, len).map_err(|_| arbitrary::Error::IncorrectFormat)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for IpNet {
+	fn schema_name() -> alloc::borrow::Cow<'static, str> {
+		"IpNet".into()
+	}
+
+	fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "IP prefix in CIDR notation, e.g. 10.0.0.0/8 or 2001:db8::/32."
+		})
+	}
+}
+
+/// Returns 32 for IPv4 and 128 for IPv6.
+fn max_prefix_len(ip: IpAddr) -> u8 {
+	if ip.is_ipv4() { 32 } else { 128 }
+}
+
+/// All-ones value for an address family of `max` bits.
+fn family_bits(max: u8) -> u128 {
+	if max == 128 {
+		u128::MAX
+	} else {
+		(1u128 << max) - 1
+	}
+}
+
+/// Network mask with the top `len` bits of a `max`-bit address set.
+fn mask_bits(len: u8, max: u8) -> u128 {
+	if len == 0 {
+		0
+	} else {
+		family_bits(max) & !family_bits(max).checked_shr(u32::from(len)).unwrap_or(0)
+	}
+}
+
+/// A longest-prefix-match table mapping [`IpNet`] prefixes to values.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{IpNet, PrefixTable};
+///
+/// let mut table = PrefixTable::new();
+/// table.insert("10.0.0.0/8".parse::<IpNet>().unwrap(), "corp");
+/// table.insert("10.1.0.0/16".parse::<IpNet>().unwrap(), "lab");
+///
+/// let (net, label) = table.lookup("10.1.2.3".parse().unwrap()).unwrap();
+/// assert_eq!(net.to_string(), "10.1.0.0/16");
+/// assert_eq!(*label, "lab");
+/// assert!(table.lookup("192.0.2.1".parse().unwrap()).is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrefixTable<T> {
+	entries: BTreeMap<IpNet, T>,
+	v4_lens: BTreeSet<u8>,
+	v6_lens: BTreeSet<u8>,
+}
+
+impl<T> PrefixTable<T> {
+	/// Creates an empty table.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			entries: BTreeMap::new(),
+			v4_lens: BTreeSet::new(),
+			v6_lens: BTreeSet::new(),
+		}
+	}
+
+	/// Inserts a prefix, returning the value previously stored for it.
+	pub fn insert(&mut self, net: IpNet, value: T) -> Option<T> {
+		if net.is_ipv4() {
+			self.v4_lens.insert(net.prefix_len);
+		} else {
+			self.v6_lens.insert(net.prefix_len);
+		}
+		self.entries.insert(net, value)
+	}
+
+	/// Returns the value stored for exactly this prefix.
+	#[must_use]
+	pub fn get(&self, net: &IpNet) -> Option<&T> {
+		self.entries.get(net)
+	}
+
+	/// Returns a mutable reference to the value stored for exactly this prefix.
+	#[must_use]
+	pub fn get_mut(&mut self, net: &IpNet) -> Option<&mut T> {
+		self.entries.get_mut(net)
+	}
+
+	/// Returns the most specific prefix containing `ip`, with its value.
+	#[must_use]
+	pub fn lookup(&self, ip: IpAddr) -> Option<(IpNet, &T)> {
+		self.matches(ip).next()
+	}
+
+	/// Returns every prefix containing `ip`, most specific first.
+	pub fn matches(&self, ip: IpAddr) -> impl Iterator<Item = (IpNet, &T)> + '_ {
+		let lens = if ip.is_ipv4() {
+			&self.v4_lens
+		} else {
+			&self.v6_lens
+		};
+		lens.iter().rev().filter_map(move |&len| {
+			let net = IpNet::new(ip, len).ok()?;
+			self.entries.get(&net).map(|value| (net, value))
+		})
+	}
+
+	/// Returns the number of prefixes in the table.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns `true` if the table holds no prefixes.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Iterates over all prefixes and values in address order.
+	pub fn iter(&self) -> impl Iterator<Item = (&IpNet, &T)> + '_ {
+		self.entries.iter()
+	}
+}
+
+impl<T> Default for PrefixTable<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> Extend<(IpNet, T)> for PrefixTable<T> {
+	fn extend<I: IntoIterator<Item = (IpNet, T)>>(&mut self, iter: I) {
+		for (net, value) in iter {
+			self.insert(net, value);
+		}
+	}
+}
+
+impl<T> FromIterator<(IpNet, T)> for PrefixTable<T> {
+	fn from_iter<I: IntoIterator<Item = (IpNet, T)>>(iter: I) -> Self {
+		let mut table = Self::new();
+		table.extend(iter);
+		table
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn net(s: &str) -> IpNet {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn test_parse_net() {
+		assert_eq!(net("10.1.2.3/8").to_string(), "10.0.0.0/8");
+		assert_eq!(net("2001:db8::1/32").to_string(), "2001:db8::/32");
+		assert_eq!(net("0.0.0.0/0").prefix_len(), 0);
+		assert_eq!(
+			net("::/0").last().to_string(),
+			"ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"
+		);
+		assert!(matches!(
+			"10.0.0.0/33".parse::<IpNet>().unwrap_err(),
+			NetSemError::InvalidPrefix(_)
+		));
+		assert!("10.0.0.0".parse::<IpNet>().is_err());
+		assert!("10.0.0.0/+8".parse::<IpNet>().is_err());
+		assert!("10.0.0.0/".parse::<IpNet>().is_err());
+		assert!("::/129".parse::<IpNet>().is_err());
+	}
+
+	#[test]
+	fn test_net_bounds() {
+		let v4 = net("192.168.1.0/24");
+		assert_eq!(v4.network().to_string(), "192.168.1.0");
+		assert_eq!(v4.last().to_string(), "192.168.1.255");
+		assert_eq!(v4.netmask().to_string(), "255.255.255.0");
+		assert_eq!(v4.hostmask().to_string(), "0.0.0.255");
+		assert_eq!(net("1.2.3.4/32").last().to_string(), "1.2.3.4");
+		assert_eq!(net("::1/128").network().to_string(), "::1");
+		assert_eq!(net("::1/128").last().to_string(), "::1");
+
+		let v6 = net("fe80::/10");
+		assert_eq!(
+			v6.last().to_string(),
+			"febf:ffff:ffff:ffff:ffff:ffff:ffff:ffff"
+		);
+
+		assert_eq!(
+			IpNet::from("10.1.2.3".parse::<IpAddr>().unwrap()),
+			net("10.1.2.3/32")
+		);
+		assert_eq!(
+			IpNet::from("::1".parse::<IpAddr>().unwrap()),
+			net("::1/128")
+		);
+	}
+
+	#[test]
+	fn test_net_contains() {
+		let v4 = net("10.0.0.0/8");
+		assert!(v4.contains("10.255.255.255".parse().unwrap()));
+		assert!(!v4.contains("11.0.0.0".parse().unwrap()));
+		assert!(!v4.contains("::a00:1".parse().unwrap()));
+		assert!(v4.contains_net(&net("10.1.0.0/16")));
+		assert!(!net("10.1.0.0/16").contains_net(&v4));
+		assert!(net("::/0").contains("2001:db8::1".parse().unwrap()));
+	}
+
+	#[test]
+	fn test_prefix_table() {
+		let table: PrefixTable<u32> = [
+			(net("0.0.0.0/0"), 0),
+			(net("10.0.0.0/8"), 8),
+			(net("10.1.0.0/16"), 16),
+			(net("2001:db8::/32"), 32),
+		]
+		.into_iter()
+		.collect();
+
+		assert_eq!(table.len(), 4);
+		assert_eq!(table.lookup("10.1.9.9".parse().unwrap()).unwrap().1, &16);
+		assert_eq!(table.lookup("10.2.0.1".parse().unwrap()).unwrap().1, &8);
+		assert_eq!(table.lookup("8.8.8.8".parse().unwrap()).unwrap().1, &0);
+		assert_eq!(table.lookup("2001:db8::1".parse().unwrap()).unwrap().1, &32);
+		assert!(table.lookup("2001:db9::1".parse().unwrap()).is_none());
+
+		let all: Vec<u32> = table
+			.matches("10.1.0.1".parse().unwrap())
+			.map(|(_, v)| *v)
+			.collect();
+		assert_eq!(all, [16, 8, 0]);
+		assert_eq!(table.get(&net("10.0.0.0/8")), Some(&8));
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn test_net_serde() {
+		let json = serde_json::to_string(&net("10.0.0.0/8")).unwrap();
+		assert_eq!(json, r#""10.0.0.0/8""#);
+		let parsed: Vec<IpNet> = serde_json::from_str(r#"["2001:db8::/32"]"#).unwrap();
+		assert_eq!(parsed, [net("2001:db8::/32")]);
+		assert!(serde_json::from_str::<IpNet>(r#""10.0.0.1""#).is_err());
+	}
+}
@@ -0,0 +1,443 @@
+/* src/plan.rs */
+
+use crate::error::NetSemError;
+use crate::ip::{bits_to_ip, ip_to_bits};
+use crate::net::IpNet;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Rounds a prefix length down to a nibble boundary (a multiple of 4).
+///
+/// Nibble-aligned IPv6 prefixes map to whole labels under `ip6.arpa`, so each
+/// one can be delegated for reverse DNS on its own.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::plan::nibble_floor;
+///
+/// assert_eq!(nibble_floor(56), 56);
+/// assert_eq!(nibble_floor(62), 60);
+/// ```
+#[must_use]
+pub fn nibble_floor(prefix_len: u8) -> u8 {
+	prefix_len & !3
+}
+
+/// Hands out nibble-aligned child prefixes of an IPv6 pool, lowest first.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::IpNet;
+/// use netsem::plan::PrefixAllocator;
+///
+/// let mut site = PrefixAllocator::new("2001:db8:1::/48".parse().unwrap()).unwrap();
+/// site.reserve("2001:db8:1::/56".parse().unwrap()).unwrap();
+///
+/// let building = site.allocate(56).unwrap();
+/// assert_eq!(building.to_string(), "2001:db8:1:100::/56");
+///
+/// // A /62 request is widened to the enclosing nibble boundary.
+/// assert_eq!(site.allocate(62).unwrap().to_string(), "2001:db8:1:200::/60");
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrefixAllocator {
+	parent: IpNet,
+	allocated: BTreeSet<IpNet>,
+}
+
+impl PrefixAllocator {
+	/// Creates an allocator for `parent`.
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPlan)` if `parent` is not an IPv6 prefix on a nibble boundary.
+	pub fn new(parent: IpNet) -> Result<Self, NetSemError> {
+		check_base(&parent)?;
+		Ok(Self {
+			parent,
+			allocated: BTreeSet::new(),
+		})
+	}
+
+	/// Returns the pool prefix.
+	#[must_use]
+	pub fn parent(&self) -> IpNet {
+		self.parent
+	}
+
+	/// Allocates the lowest free child prefix of at least `prefix_len`'s size.
+	///
+	/// `prefix_len` is rounded down with [`nibble_floor`], so the returned prefix
+	/// is never smaller than requested.
+	///
+	/// # Returns
+	///
+	/// * `Ok(IpNet)` with the allocated prefix.
+	/// * `Err(NetSemError::InvalidPlan)` if `prefix_len` is shorter than the pool's or exceeds 128.
+	/// * `Err(NetSemError::PlanExhausted)` if no free prefix of that size is left.
+	pub fn allocate(&mut self, prefix_len: u8) -> Result<IpNet, NetSemError> {
+		if prefix_len > 128 || prefix_len < self.parent.prefix_len() {
+			return Err(NetSemError::InvalidPlan(format!(
+				"/{prefix_len} does not fit inside {}",
+				self.parent
+			)));
+		}
+		let len = nibble_floor(prefix_len);
+		let host_bits = 128 - u32::from(len);
+		let exhausted = || NetSemError::PlanExhausted {
+			parent: format!("{}", self.parent),
+			prefix_len: len,
+		};
+
+		let end = ip_to_bits(self.parent.last());
+		let mut start = ip_to_bits(self.parent.network());
+		for used in &self.allocated {
+			let used_start = ip_to_bits(used.network());
+			let used_end = ip_to_bits(used.last());
+			if used_end < start {
+				continue;
+			}
+			if used_start > start | low_mask(host_bits) {
+				break;
+			}
+			start = used_end
+				.checked_add(1)
+				.and_then(|next| align_up(next, host_bits))
+				.ok_or_else(exhausted)?;
+		}
+		if start > end {
+			return Err(exhausted());
+		}
+
+		let net = IpNet::new(bits_to_ip(start, false), len).expect("length is at most 128");
+		self.allocated.insert(net);
+		Ok(net)
+	}
+
+	/// Marks an existing assignment as used (it need not be nibble-aligned).
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPlan)` if `net` lies outside the pool or overlaps
+	///   an allocated prefix.
+	pub fn reserve(&mut self, net: IpNet) -> Result<(), NetSemError> {
+		if !self.parent.contains_net(&net) {
+			return Err(NetSemError::InvalidPlan(format!(
+				"{net} is outside {}",
+				self.parent
+			)));
+		}
+		if let Some(other) = self
+			.allocated
+			.iter()
+			.find(|other| other.contains_net(&net) || net.contains_net(other))
+		{
+			return Err(NetSemError::InvalidPlan(format!("{net} overlaps {other}")));
+		}
+		self.allocated.insert(net);
+		Ok(())
+	}
+
+	/// Returns an allocated or reserved prefix to the pool.
+	///
+	/// Returns `false` if `net` was not allocated.
+	pub fn release(&mut self, net: &IpNet) -> bool {
+		self.allocated.remove(net)
+	}
+
+	/// Iterates over the allocated and reserved prefixes in address order.
+	pub fn allocated(&self) -> impl Iterator<Item = IpNet> + '_ {
+		self.allocated.iter().copied()
+	}
+}
+
+/// A numbering scheme that encodes named fields into IPv6 prefix bits.
+///
+/// Fields follow the base prefix in declaration order, each spanning a whole
+/// number of nibbles, so every level of the hierarchy is nibble-aligned.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::plan::Scheme;
+///
+/// let scheme = Scheme::new("2001:db8::/32".parse().unwrap())
+///     .and_then(|s| s.field("site", 16))
+///     .and_then(|s| s.field("building", 8))
+///     .and_then(|s| s.field("vlan", 8))
+///     .unwrap();
+///
+/// assert_eq!(scheme.encode(&[0x12]).unwrap().to_string(), "2001:db8:12::/48");
+/// let vlan = scheme.encode(&[0x12, 3, 0x40]).unwrap();
+/// assert_eq!(vlan.to_string(), "2001:db8:12:340::/64");
+///
+/// let fields = scheme.decode("2001:db8:12:340::1".parse::<std::net::IpAddr>().unwrap()).unwrap();
+/// assert_eq!(fields, [("site", 0x12), ("building", 3), ("vlan", 0x40)]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scheme {
+	base: IpNet,
+	fields: Vec<(String, u8)>,
+}
+
+impl Scheme {
+	/// Starts a scheme below `base`.
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPlan)` if `base` is not an IPv6 prefix on a nibble boundary.
+	pub fn new(base: IpNet) -> Result<Self, NetSemError> {
+		check_base(&base)?;
+		Ok(Self {
+			base,
+			fields: Vec::new(),
+		})
+	}
+
+	/// Appends a field of `bits` bits after the previous ones.
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPlan)` if `bits` is zero or not a multiple of 4,
+	///   the name is already used, or the fields would run past 128 bits.
+	pub fn field(mut self, name: impl Into<String>, bits: u8) -> Result<Self, NetSemError> {
+		let name = name.into();
+		if bits == 0 || !bits.is_multiple_of(4) {
+			return Err(NetSemError::InvalidPlan(format!(
+				"field {name:?} must span a positive multiple of 4 bits, not {bits}"
+			)));
+		}
+		if self.fields.iter().any(|(existing, _)| *existing == name) {
+			return Err(NetSemError::InvalidPlan(format!(
+				"duplicate field {name:?}"
+			)));
+		}
+		if u32::from(self.prefix_len()) + u32::from(bits) > 128 {
+			return Err(NetSemError::InvalidPlan(format!(
+				"field {name:?} runs past the end of the address"
+			)));
+		}
+		self.fields.push((name, bits));
+		Ok(self)
+	}
+
+	/// Returns the base prefix.
+	#[must_use]
+	pub fn base(&self) -> IpNet {
+		self.base
+	}
+
+	/// Returns the prefix length after all fields.
+	#[must_use]
+	pub fn prefix_len(&self) -> u8 {
+		self.base.prefix_len() + self.fields.iter().map(|&(_, bits)| bits).sum::<u8>()
+	}
+
+	/// Iterates over the field names and widths in order.
+	pub fn fields(&self) -> impl Iterator<Item = (&str, u8)> + '_ {
+		self
+			.fields
+			.iter()
+			.map(|(name, bits)| (name.as_str(), *bits))
+	}
+
+	/// Builds the prefix for the given values of the leading fields.
+	///
+	/// Passing fewer values than fields yields the shorter, enclosing prefix
+	/// (e.g., only a site number gives the site's `/48`).
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPlan)` if there are more values than fields or a
+	///   value does not fit its field.
+	pub fn encode(&self, values: &[u128]) -> Result<IpNet, NetSemError> {
+		if values.len() > self.fields.len() {
+			return Err(NetSemError::InvalidPlan(format!(
+				"{} values given for {} fields",
+				values.len(),
+				self.fields.len()
+			)));
+		}
+		let mut bits = ip_to_bits(self.base.network());
+		let mut offset = u32::from(self.base.prefix_len());
+		for (&value, (name, width)) in values.iter().zip(&self.fields) {
+			let width = u32::from(*width);
+			if value & !low_mask(width) != 0 {
+				return Err(NetSemError::InvalidPlan(format!(
+					"value {value} does not fit field {name:?} ({width} bits)"
+				)));
+			}
+			offset += width;
+			bits |= value.checked_shl(128 - offset).unwrap_or(0);
+		}
+		Ok(IpNet::new(bits_to_ip(bits, false), offset as u8).expect("fields end within 128 bits"))
+	}
+
+	/// Reads the field values back out of an address or prefix.
+	///
+	/// Only fields entirely within the prefix length are returned, so an
+	/// address yields every field and a building's `/56` yields site and
+	/// building but not VLAN.
+	///
+	/// Returns `None` if `net` is not inside the base prefix.
+	#[must_use]
+	pub fn decode(&self, net: impl Into<IpNet>) -> Option<Vec<(&str, u128)>> {
+		let net = net.into();
+		if !self.base.contains_net(&net) {
+			return None;
+		}
+		let bits = ip_to_bits(net.network());
+		let mut offset = u32::from(self.base.prefix_len());
+		let mut out = Vec::new();
+		for (name, width) in &self.fields {
+			let width = u32::from(*width);
+			if offset + width > u32::from(net.prefix_len()) {
+				break;
+			}
+			offset += width;
+			let value = bits.checked_shr(128 - offset).unwrap_or(0) & low_mask(width);
+			out.push((name.as_str(), value));
+		}
+		Some(out)
+	}
+}
+
+/// Checks that `base` is an IPv6 prefix on a nibble boundary.
+fn check_base(base: &IpNet) -> Result<(), NetSemError> {
+	if !base.is_ipv6() {
+		return Err(NetSemError::InvalidPlan(format!(
+			"{base} is not an IPv6 prefix"
+		)));
+	}
+	if !base.prefix_len().is_multiple_of(4) {
+		return Err(NetSemError::InvalidPlan(format!(
+			"{base} is not on a nibble boundary"
+		)));
+	}
+	Ok(())
+}
+
+/// Value with the low `bits` bits set.
+fn low_mask(bits: u32) -> u128 {
+	1u128.checked_shl(bits).map_or(u128::MAX, |size| size - 1)
+}
+
+/// Rounds `value` up to a multiple of `2^bits`, or `None` on overflow.
+fn align_up(value: u128, bits: u32) -> Option<u128> {
+	let mask = low_mask(bits);
+	value.checked_add(mask).map(|v| v & !mask)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::net::IpAddr;
+
+	fn net(s: &str) -> IpNet {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn test_allocate() {
+		let mut pool = PrefixAllocator::new(net("2001:db8::/56")).unwrap();
+		assert_eq!(pool.allocate(64).unwrap(), net("2001:db8::/64"));
+		assert_eq!(pool.allocate(60).unwrap(), net("2001:db8:0:10::/60"));
+		assert_eq!(pool.allocate(64).unwrap(), net("2001:db8:0:1::/64"));
+		assert!(pool.release(&net("2001:db8::/64")));
+		assert_eq!(pool.allocate(63).unwrap(), net("2001:db8:0:20::/60"));
+		assert!(pool.release(&net("2001:db8:0:1::/64")));
+		assert!(!pool.release(&net("2001:db8:0:1::/64")));
+		assert_eq!(pool.allocate(60).unwrap(), net("2001:db8::/60"));
+		assert!(matches!(
+			pool.allocate(48),
+			Err(NetSemError::InvalidPlan(_))
+		));
+
+		let mut small = PrefixAllocator::new(net("2001:db8::/60")).unwrap();
+		for _ in 0..16 {
+			small.allocate(64).unwrap();
+		}
+		assert!(matches!(
+			small.allocate(64),
+			Err(NetSemError::PlanExhausted { prefix_len: 64, .. })
+		));
+		assert_eq!(small.allocated().count(), 16);
+
+		// The top of the address space does not overflow.
+		let mut top = PrefixAllocator::new(net("ffff:ffff:ffff:ffff:ffff:ffff:ffff:fff0/124")).unwrap();
+		assert_eq!(top.allocate(124).unwrap().prefix_len(), 124);
+		assert!(top.allocate(128).is_err());
+	}
+
+	#[test]
+	fn test_reserve() {
+		let mut pool = PrefixAllocator::new(net("2001:db8::/48")).unwrap();
+		pool.reserve(net("2001:db8:0:3::/64")).unwrap();
+		assert!(pool.reserve(net("2001:db8::/62")).is_err());
+		assert!(pool.reserve(net("2001:db8:1::/64")).is_err());
+		assert_eq!(pool.allocate(60).unwrap(), net("2001:db8:0:10::/60"));
+		assert_eq!(pool.allocate(64).unwrap(), net("2001:db8::/64"));
+
+		assert!(PrefixAllocator::new(net("10.0.0.0/8")).is_err());
+		assert!(PrefixAllocator::new(net("2001:db8::/46")).is_err());
+	}
+
+	#[test]
+	fn test_scheme() {
+		let scheme = Scheme::new(net("2001:db8::/32"))
+			.and_then(|s| s.field("site", 16))
+			.and_then(|s| s.field("building", 8))
+			.and_then(|s| s.field("vlan", 8))
+			.unwrap();
+		assert_eq!(scheme.prefix_len(), 64);
+		assert_eq!(scheme.encode(&[]).unwrap(), net("2001:db8::/32"));
+		assert_eq!(
+			scheme.encode(&[0xffff, 0xff, 0xff]).unwrap(),
+			net("2001:db8:ffff:ffff::/64")
+		);
+		assert!(scheme.encode(&[0x1_0000]).is_err());
+		assert!(scheme.encode(&[1, 2, 3, 4]).is_err());
+
+		assert_eq!(
+			scheme.decode(net("2001:db8:7:500::/56")).unwrap(),
+			[("site", 7), ("building", 5)]
+		);
+		assert_eq!(
+			scheme.decode("2001:db9::1".parse::<IpAddr>().unwrap()),
+			None
+		);
+
+		for (site, building, vlan) in [(0, 0, 0), (1, 2, 3), (0xbeef, 0x80, 0x7f)] {
+			let encoded = scheme.encode(&[site, building, vlan]).unwrap();
+			let decoded: Vec<u128> = scheme
+				.decode(encoded)
+				.unwrap()
+				.into_iter()
+				.map(|(_, v)| v)
+				.collect();
+			assert_eq!(decoded, [site, building, vlan]);
+		}
+	}
+
+	#[test]
+	fn test_scheme_validation() {
+		let base = Scheme::new(net("2001:db8::/32")).unwrap();
+		assert!(base.clone().field("site", 6).is_err());
+		assert!(base.clone().field("site", 0).is_err());
+		assert!(base.clone().field("iid", 100).is_err());
+		assert!(base.field("a", 8).and_then(|s| s.field("a", 8)).is_err());
+		let full = Scheme::new(net("::/0"))
+			.and_then(|s| s.field("all", 128))
+			.unwrap();
+		let all = u128::MAX;
+		assert_eq!(
+			full.encode(&[all]).unwrap(),
+			net("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128")
+		);
+		assert_eq!(full.decode(net("::1/128")).unwrap(), [("all", 1)]);
+	}
+}
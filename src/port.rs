@@ -1,6 +1,14 @@
 /* src/port.rs */
 
 use crate::error::NetSemError;
+use crate::proto::Protocol;
+use crate::services;
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+use core::fmt;
+use core::num::NonZeroU16;
+use core::ops::RangeInclusive;
+use core::str::FromStr;
 
 #[cfg(feature = "check")]
 use std::net::{IpAddr, SocketAddr};
@@ -8,9 +16,15 @@ use std::net::{IpAddr, SocketAddr};
 use std::time::Duration;
 
 /// Classification of a port number.
+///
+/// The string form (used by `Display`, `FromStr` and serde) is the lowercase
+/// variant name: `"system"`, `"user"` or `"dynamic"`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum PortClass {
 	/// System ports (0-1023).
 	System,
@@ -20,6 +34,41 @@ pub enum PortClass {
 	Dynamic,
 }
 
+impl PortClass {
+	/// Every class, in ascending port order.
+	pub const ALL: &'static [Self] = &[Self::System, Self::User, Self::Dynamic];
+
+	/// Returns the stable lowercase name of the class.
+	#[must_use]
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::System => "system",
+			Self::User => "user",
+			Self::Dynamic => "dynamic",
+		}
+	}
+}
+
+impl fmt::Display for PortClass {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for PortClass {
+	type Err = NetSemError;
+
+	/// Parses the name produced by [`PortClass::as_str`].
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"system" => Ok(Self::System),
+			"user" => Ok(Self::User),
+			"dynamic" => Ok(Self::Dynamic),
+			_ => Err(NetSemError::UnknownClass(s.to_owned())),
+		}
+	}
+}
+
 /// Validates a port number, disallowing port 0.
 ///
 /// # Returns
@@ -60,6 +109,24 @@ pub fn validate_port_or_zero(_port: u16) -> Result<(), NetSemError> {
 	Ok(())
 }
 
+/// Returns the inclusive port range covered by a class.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{PortClass, port_class_range};
+///
+/// assert_eq!(port_class_range(PortClass::User), 1024..=49151);
+/// ```
+#[must_use]
+pub fn port_class_range(class: PortClass) -> RangeInclusive<u16> {
+	match class {
+		PortClass::System => 0..=1023,
+		PortClass::User => 1024..=49151,
+		PortClass::Dynamic => 49152..=65535,
+	}
+}
+
 /// Classifies a port number into its IANA range.
 ///
 /// # Examples
@@ -82,21 +149,976 @@ pub fn classify_port(p: u16) -> PortClass {
 	}
 }
 
-/// Internal helper to check binding for TCP or UDP.
+/// A non-zero port number.
+///
+/// Use it for ports that must name a real endpoint; [`AnyPort`] also admits
+/// the 0 wildcard. Parsing accepts everything [`parse_port`] does. Serde
+/// writes the number and reads either a number or a string.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{Port, PortClass};
+///
+/// let port = Port::try_from(8080).unwrap();
+/// assert_eq!(port.get(), 8080);
+/// assert_eq!(port.class(), PortClass::User);
+/// assert_eq!("https".parse::<Port>().unwrap().get(), 443);
+/// assert!(Port::try_from(0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Port(NonZeroU16);
+
+impl Port {
+	/// Returns the port if it is non-zero.
+	#[must_use]
+	pub const fn new(port: u16) -> Option<Self> {
+		match NonZeroU16::new(port) {
+			Some(port) => Some(Self(port)),
+			None => None,
+		}
+	}
+
+	/// Returns the port number.
+	#[must_use]
+	pub const fn get(self) -> u16 {
+		self.0.get()
+	}
+
+	/// Classifies the port into its IANA range.
+	#[must_use]
+	pub fn class(self) -> PortClass {
+		classify_port(self.get())
+	}
+}
+
+/// A port number including 0, which asks the OS to pick an ephemeral port when binding.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{AnyPort, Port};
+///
+/// assert!(AnyPort::from(0).is_wildcard());
+/// assert!(Port::try_from(AnyPort::from(0)).is_err());
+/// assert_eq!("0".parse::<AnyPort>().unwrap(), AnyPort::new(0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct AnyPort(u16);
+
+impl AnyPort {
+	/// Wraps a port number.
+	#[must_use]
+	pub const fn new(port: u16) -> Self {
+		Self(port)
+	}
+
+	/// Returns the port number.
+	#[must_use]
+	pub const fn get(self) -> u16 {
+		self.0
+	}
+
+	/// Returns `true` for port 0.
+	#[must_use]
+	pub const fn is_wildcard(self) -> bool {
+		self.0 == 0
+	}
+
+	/// Classifies the port into its IANA range.
+	#[must_use]
+	pub fn class(self) -> PortClass {
+		classify_port(self.0)
+	}
+}
+
+/// A non-zero System port (1-1023), which usually needs privileges to bind.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::PrivilegedPort;
+///
+/// assert_eq!(PrivilegedPort::try_from(443).unwrap().get(), 443);
+/// assert!(PrivilegedPort::try_from(8080).is_err());
+/// assert!("ssh".parse::<PrivilegedPort>().is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PrivilegedPort(NonZeroU16);
+
+impl PrivilegedPort {
+	/// The lowest privileged port.
+	pub const MIN: u16 = 1;
+	/// The highest privileged port.
+	pub const MAX: u16 = 1023;
+
+	/// Returns the port if it is in 1-1023.
+	#[must_use]
+	pub const fn new(port: u16) -> Option<Self> {
+		match NonZeroU16::new(port) {
+			Some(nz) if port <= Self::MAX => Some(Self(nz)),
+			_ => None,
+		}
+	}
+
+	/// Returns the port number.
+	#[must_use]
+	pub const fn get(self) -> u16 {
+		self.0.get()
+	}
+
+	/// Returns the port's class, which is always [`PortClass::System`].
+	#[must_use]
+	pub fn class(self) -> PortClass {
+		PortClass::System
+	}
+}
+
+/// A port in the Dynamic range (49152-65535) suggested for ephemeral use.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{EphemeralPort, Port};
+///
+/// let port = EphemeralPort::try_from(50000).unwrap();
+/// assert_eq!(Port::from(port).get(), 50000);
+/// assert!(EphemeralPort::try_from(49151).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EphemeralPort(NonZeroU16);
+
+impl EphemeralPort {
+	/// The lowest ephemeral port.
+	pub const MIN: u16 = 49152;
+	/// The highest ephemeral port.
+	pub const MAX: u16 = 65535;
+
+	/// Returns the port if it is in 49152-65535.
+	#[must_use]
+	pub const fn new(port: u16) -> Option<Self> {
+		match NonZeroU16::new(port) {
+			Some(nz) if port >= Self::MIN => Some(Self(nz)),
+			_ => None,
+		}
+	}
+
+	/// Returns the port number.
+	#[must_use]
+	pub const fn get(self) -> u16 {
+		self.0.get()
+	}
+
+	/// Returns the port's class, which is always [`PortClass::Dynamic`].
+	#[must_use]
+	pub fn class(self) -> PortClass {
+		PortClass::Dynamic
+	}
+}
+
+impl From<NonZeroU16> for Port {
+	fn from(port: NonZeroU16) -> Self {
+		Self(port)
+	}
+}
+
+impl From<Port> for NonZeroU16 {
+	fn from(port: Port) -> Self {
+		port.0
+	}
+}
+
+impl From<u16> for AnyPort {
+	fn from(port: u16) -> Self {
+		Self(port)
+	}
+}
+
+impl From<Port> for AnyPort {
+	fn from(port: Port) -> Self {
+		Self(port.get())
+	}
+}
+
+impl From<PrivilegedPort> for Port {
+	fn from(port: PrivilegedPort) -> Self {
+		Self(port.0)
+	}
+}
+
+impl From<EphemeralPort> for Port {
+	fn from(port: EphemeralPort) -> Self {
+		Self(port.0)
+	}
+}
+
+impl TryFrom<u16> for Port {
+	type Error = NetSemError;
+
+	fn try_from(port: u16) -> Result<Self, Self::Error> {
+		Self::new(port).ok_or(NetSemError::InvalidPort(port))
+	}
+}
+
+impl TryFrom<u16> for PrivilegedPort {
+	type Error = NetSemError;
+
+	fn try_from(port: u16) -> Result<Self, Self::Error> {
+		Self::new(port).ok_or(NetSemError::PortNotInRange {
+			port,
+			min: Self::MIN,
+			max: Self::MAX,
+		})
+	}
+}
+
+impl TryFrom<u16> for EphemeralPort {
+	type Error = NetSemError;
+
+	fn try_from(port: u16) -> Result<Self, Self::Error> {
+		Self::new(port).ok_or(NetSemError::PortNotInRange {
+			port,
+			min: Self::MIN,
+			max: Self::MAX,
+		})
+	}
+}
+
+impl TryFrom<AnyPort> for Port {
+	type Error = NetSemError;
+
+	fn try_from(port: AnyPort) -> Result<Self, Self::Error> {
+		Self::try_from(port.get())
+	}
+}
+
+impl TryFrom<Port> for PrivilegedPort {
+	type Error = NetSemError;
+
+	fn try_from(port: Port) -> Result<Self, Self::Error> {
+		Self::try_from(port.get())
+	}
+}
+
+impl TryFrom<Port> for EphemeralPort {
+	type Error = NetSemError;
+
+	fn try_from(port: Port) -> Result<Self, Self::Error> {
+		Self::try_from(port.get())
+	}
+}
+
+impl From<Port> for u16 {
+	fn from(port: Port) -> Self {
+		port.get()
+	}
+}
+
+impl From<AnyPort> for u16 {
+	fn from(port: AnyPort) -> Self {
+		port.get()
+	}
+}
+
+impl From<PrivilegedPort> for u16 {
+	fn from(port: PrivilegedPort) -> Self {
+		port.get()
+	}
+}
+
+impl From<EphemeralPort> for u16 {
+	fn from(port: EphemeralPort) -> Self {
+		port.get()
+	}
+}
+
+/// Shared conversions behind the port newtypes' string, serde and schema impls.
+trait PortNewtype: Sized {
+	#[cfg(feature = "serde")]
+	const MIN: u16;
+	#[cfg(feature = "serde")]
+	const MAX: u16;
+
+	fn from_u16(port: u16) -> Result<Self, NetSemError>;
+}
+
+impl PortNewtype for Port {
+	#[cfg(feature = "serde")]
+	const MIN: u16 = 1;
+	#[cfg(feature = "serde")]
+	const MAX: u16 = u16::MAX;
+
+	fn from_u16(port: u16) -> Result<Self, NetSemError> {
+		Self::try_from(port)
+	}
+}
+
+impl PortNewtype for AnyPort {
+	#[cfg(feature = "serde")]
+	const MIN: u16 = 0;
+	#[cfg(feature = "serde")]
+	const MAX: u16 = u16::MAX;
+
+	fn from_u16(port: u16) -> Result<Self, NetSemError> {
+		Ok(Self(port))
+	}
+}
+
+impl PortNewtype for PrivilegedPort {
+	#[cfg(feature = "serde")]
+	const MIN: u16 = Self::MIN;
+	#[cfg(feature = "serde")]
+	const MAX: u16 = Self::MAX;
+
+	fn from_u16(port: u16) -> Result<Self, NetSemError> {
+		Self::try_from(port)
+	}
+}
+
+impl PortNewtype for EphemeralPort {
+	#[cfg(feature = "serde")]
+	const MIN: u16 = Self::MIN;
+	#[cfg(feature = "serde")]
+	const MAX: u16 = Self::MAX;
+
+	fn from_u16(port: u16) -> Result<Self, NetSemError> {
+		Self::try_from(port)
+	}
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_port<'de, T: PortNewtype, D: serde::Deserializer<'de>>(
+	deserializer: D,
+) -> Result<T, D::Error> {
+	struct PortVisitor<T>(core::marker::PhantomData<T>);
+
+	impl<T: PortNewtype> serde::de::Visitor<'_> for PortVisitor<T> {
+		type Value = T;
+
+		fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			write!(
+				f,
+				"a port number from {} to {} or a service name",
+				T::MIN,
+				T::MAX
+			)
+		}
+
+		fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<T, E> {
+			let port = u16::try_from(v)
+				.map_err(|_| E::custom(NetSemError::PortOutOfRange(alloc::format!("{v}"))))?;
+			T::from_u16(port).map_err(E::custom)
+		}
+
+		fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<T, E> {
+			u64::try_from(v)
+				.map_err(|_| E::custom(NetSemError::SignedPort(alloc::format!("{v}"))))
+				.and_then(|v| self.visit_u64(v))
+		}
+
+		fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<T, E> {
+			parse_port(v).and_then(T::from_u16).map_err(E::custom)
+		}
+	}
+
+	deserializer.deserialize_any(PortVisitor(core::marker::PhantomData))
+}
+
+#[cfg(feature = "schemars")]
+fn port_schema<T: PortNewtype>() -> schemars::Schema {
+	schemars::json_schema!({
+		"type": ["integer", "string"],
+		"minimum": T::MIN,
+		"maximum": T::MAX,
+		"description": "Port number, or an IANA service name such as \"https\"."
+	})
+}
+
+/// Implements `Display`, `FromStr`, serde and `JsonSchema` for a [`PortNewtype`].
+macro_rules! port_newtype_impls {
+	($($ty:ident),+) => {$(
+		impl fmt::Display for $ty {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				write!(f, "{}", self.get())
+			}
+		}
+
+		impl FromStr for $ty {
+			type Err = NetSemError;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				Self::from_u16(parse_port(s)?)
+			}
+		}
+
+		#[cfg(feature = "serde")]
+		impl serde::Serialize for $ty {
+			fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.serialize_u16(self.get())
+			}
+		}
+
+		#[cfg(feature = "serde")]
+		impl<'de> serde::Deserialize<'de> for $ty {
+			fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				deserialize_port(deserializer)
+			}
+		}
+
+		#[cfg(feature = "schemars")]
+		impl schemars::JsonSchema for $ty {
+			fn schema_name() -> alloc::borrow::Cow<'static, str> {
+				stringify!($ty).into()
+			}
+
+			fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+				port_schema::<Self>()
+			}
+		}
+	)+};
+}
+
+port_newtype_impls!(Port, AnyPort, PrivilegedPort, EphemeralPort);
+
+/// Parses a port given as a number or a service name, with an optional `/protocol` suffix.
+///
+/// Numbers must be plain decimal digits. Service names are resolved with the
+/// built-in IANA snapshot ([`services::service_ports`]); without a suffix the
+/// first registered port of any protocol is used. Use
+/// [`ServiceRegistry::parse_port`](services::ServiceRegistry::parse_port) to
+/// resolve against local `/etc/services` additions.
+///
+/// # Returns
+///
+/// * `Ok(u16)` with the port number.
+/// * `Err(NetSemError::EmptyPort)`, `PortWhitespace`, `SignedPort` or
+///   `PortOutOfRange` for malformed numbers.
+/// * `Err(NetSemError::UnknownService)` if the name is not registered for the protocol.
+/// * `Err(NetSemError::InvalidProtocol)` or `InvalidTransportPort` for a bad suffix.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{NetSemError, parse_port};
+///
+/// assert_eq!(parse_port("8080").unwrap(), 8080);
+/// assert_eq!(parse_port("https").unwrap(), 443);
+/// assert_eq!(parse_port("http-alt/tcp").unwrap(), 8080);
+/// assert!(matches!(parse_port("+80"), Err(NetSemError::SignedPort(_))));
+/// assert!(matches!(parse_port(" 80"), Err(NetSemError::PortWhitespace(_))));
+/// assert!(matches!(parse_port("65536"), Err(NetSemError::PortOutOfRange(_))));
+/// assert!(matches!(parse_port("syslog/tcp"), Err(NetSemError::UnknownService(_))));
+/// ```
+pub fn parse_port(s: &str) -> Result<u16, NetSemError> {
+	parse_port_with(s, None, |name| services::service_ports(name).collect()).map(|p| p.0)
+}
+
+/// Parses a port like [`parse_port`] and pairs it with its transport protocol.
+///
+/// The protocol comes from the `/protocol` suffix when present and is
+/// `default_proto` otherwise; service names must be registered for it.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{Protocol, TransportPort, parse_port_spec};
+///
+/// assert_eq!(parse_port_spec("53/udp", Protocol::Tcp).unwrap(), TransportPort::udp(53));
+/// assert_eq!(parse_port_spec("syslog", Protocol::Udp).unwrap(), TransportPort::udp(514));
+/// assert_eq!(parse_port_spec("8080", Protocol::Tcp).unwrap(), TransportPort::tcp(8080));
+/// assert!(parse_port_spec("syslog", Protocol::Tcp).is_err());
+/// ```
+pub fn parse_port_spec(s: &str, default_proto: Protocol) -> Result<TransportPort, NetSemError> {
+	parse_port_with(s, Some(default_proto), |name| {
+		services::service_ports(name).collect()
+	})
+	.and_then(|(port, proto)| TransportPort::new(proto.unwrap_or(default_proto), port))
+}
+
+/// Shared implementation of the port parsers; `lookup` returns the ports of a service name.
+pub(crate) fn parse_port_with(
+	s: &str,
+	default_proto: Option<Protocol>,
+	lookup: impl FnOnce(&str) -> Vec<TransportPort>,
+) -> Result<(u16, Option<Protocol>), NetSemError> {
+	if s.chars().any(char::is_whitespace) {
+		return Err(NetSemError::PortWhitespace(s.to_owned()));
+	}
+	let (port, proto) = match s.split_once('/') {
+		Some((port, proto)) => {
+			let proto = proto.parse::<Protocol>()?;
+			if !proto.has_ports() {
+				return Err(NetSemError::InvalidTransportPort(s.to_owned()));
+			}
+			(port, Some(proto))
+		}
+		None => (s, default_proto),
+	};
+	match port.bytes().next() {
+		None => Err(NetSemError::EmptyPort),
+		Some(b'+' | b'-') => Err(NetSemError::SignedPort(s.to_owned())),
+		Some(_) if port.bytes().all(|b| b.is_ascii_digit()) => port
+			.parse::<u16>()
+			.map(|n| (n, proto))
+			.map_err(|_| NetSemError::PortOutOfRange(port.to_owned())),
+		Some(_) => lookup(port)
+			.into_iter()
+			.find(|p| proto.is_none_or(|proto| p.proto() == proto))
+			.map(|p| (p.port(), proto))
+			.ok_or_else(|| NetSemError::UnknownService(s.to_owned())),
+	}
+}
+
+/// A port number qualified by its transport protocol, as in `"53/udp"`.
+///
+/// Only protocols with ports ([`Protocol::has_ports`]) are accepted. The
+/// string form (used by `Display`, `FromStr` and serde) is `port/protocol`
+/// with the protocol keyword; the protocol may also be given by number.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{PortClass, Protocol, TransportPort};
+///
+/// let dns: TransportPort = "53/udp".parse().unwrap();
+/// assert_eq!(dns, TransportPort::udp(53));
+/// assert_eq!(dns.class(), PortClass::System);
+/// assert_eq!("443/6".parse::<TransportPort>().unwrap().to_string(), "443/tcp");
+/// assert!("53/icmp".parse::<TransportPort>().is_err());
+/// assert!(TransportPort::new(Protocol::Sctp, 2905).is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TransportPort {
+	proto: Protocol,
+	port: u16,
+}
+
+impl TransportPort {
+	/// Creates a transport port, rejecting protocols without ports.
+	///
+	/// # Returns
+	///
+	/// * `Ok(TransportPort)` if `proto` carries ports.
+	/// * `Err(NetSemError::InvalidTransportPort)` otherwise.
+	pub fn new(proto: Protocol, port: u16) -> Result<Self, NetSemError> {
+		if !proto.has_ports() {
+			return Err(NetSemError::InvalidTransportPort(alloc::format!(
+				"{port}/{proto}"
+			)));
+		}
+		Ok(Self::new_unchecked(proto, port))
+	}
+
+	/// Creates a transport port for a protocol the caller knows carries ports.
+	pub(crate) fn new_unchecked(proto: Protocol, port: u16) -> Self {
+		debug_assert!(proto.has_ports(), "{proto} has no ports");
+		Self {
+			proto: proto.canonical(),
+			port,
+		}
+	}
+
+	/// Returns the transport protocol.
+	#[must_use]
+	pub const fn proto(self) -> Protocol {
+		self.proto
+	}
+
+	/// Returns the port number.
+	#[must_use]
+	pub const fn port(self) -> u16 {
+		self.port
+	}
+
+	/// Returns a TCP port.
+	#[must_use]
+	pub const fn tcp(port: u16) -> Self {
+		Self {
+			proto: Protocol::Tcp,
+			port,
+		}
+	}
+
+	/// Returns a UDP port.
+	#[must_use]
+	pub const fn udp(port: u16) -> Self {
+		Self {
+			proto: Protocol::Udp,
+			port,
+		}
+	}
+
+	/// Classifies the port number into its IANA range.
+	///
+	/// The System / User / Dynamic split is the same for every transport.
+	#[must_use]
+	pub fn class(self) -> PortClass {
+		classify_port(self.port)
+	}
+}
+
+impl fmt::Display for TransportPort {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{}", self.port, self.proto)
+	}
+}
+
+impl FromStr for TransportPort {
+	type Err = NetSemError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || NetSemError::InvalidTransportPort(s.to_owned());
+		let (port, proto) = s.split_once('/').ok_or_else(invalid)?;
+		if port.is_empty() || !port.bytes().all(|b| b.is_ascii_digit()) {
+			return Err(invalid());
+		}
+		let port = port.parse::<u16>().map_err(|_| invalid())?;
+		let proto = proto.parse::<Protocol>()?;
+		Self::new(proto, port).map_err(|_| invalid())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for TransportPort {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TransportPort {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+		s.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for TransportPort {
+	fn schema_name() -> alloc::borrow::Cow<'static, str> {
+		"TransportPort".into()
+	}
+
+	fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "Transport-qualified port such as \"53/udp\" or \"443/tcp\".",
+			"examples": ["53/udp", "443/tcp"]
+		})
+	}
+}
+
+/// An inclusive, non-empty range of ports, as in firewall rules (`"8000-8100"`).
+///
+/// The string form (used by `Display`, `FromStr` and serde) is `start-end`,
+/// or a single number when both ends match. Parsing also accepts `start:end`.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{PortClass, PortRange};
+///
+/// let range: PortRange = "8000:8100".parse().unwrap();
+/// assert_eq!(range.to_string(), "8000-8100");
+/// assert_eq!(range.len(), 101);
+/// assert!(range.contains(8080));
+/// assert_eq!(range.class(), Some(PortClass::User));
+///
+/// let wide: PortRange = "1000-50000".parse().unwrap();
+/// assert!(wide.straddles_classes());
+/// assert_eq!(wide.classes().count(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PortRange {
+	start: u16,
+	end: u16,
+}
+
+impl PortRange {
+	/// Every port, 0-65535.
+	pub const ALL: Self = Self {
+		start: 0,
+		end: u16::MAX,
+	};
+
+	/// Creates a range from its inclusive ends.
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPortRange)` if `start > end`.
+	pub fn new(start: u16, end: u16) -> Result<Self, NetSemError> {
+		if start > end {
+			return Err(NetSemError::InvalidPortRange(alloc::format!(
+				"{start}-{end}"
+			)));
+		}
+		Ok(Self { start, end })
+	}
+
+	/// Creates a range holding a single port.
+	#[must_use]
+	pub const fn single(port: u16) -> Self {
+		Self {
+			start: port,
+			end: port,
+		}
+	}
+
+	/// Returns the first port.
+	#[must_use]
+	pub const fn start(self) -> u16 {
+		self.start
+	}
+
+	/// Returns the last port.
+	#[must_use]
+	pub const fn end(self) -> u16 {
+		self.end
+	}
+
+	/// Returns the number of ports, from 1 to 65536.
+	#[must_use]
+	#[allow(clippy::len_without_is_empty)]
+	pub fn len(self) -> u32 {
+		u32::from(self.end) - u32::from(self.start) + 1
+	}
+
+	/// Returns `true` if the range holds `port`.
+	#[must_use]
+	pub fn contains(self, port: u16) -> bool {
+		(self.start..=self.end).contains(&port)
+	}
+
+	/// Returns `true` if every port of `other` is in this range.
+	#[must_use]
+	pub fn contains_range(self, other: Self) -> bool {
+		self.start <= other.start && other.end <= self.end
+	}
+
+	/// Returns `true` if the ranges share at least one port.
+	#[must_use]
+	pub fn overlaps(self, other: Self) -> bool {
+		self.start <= other.end && other.start <= self.end
+	}
+
+	/// Returns `true` if the ranges do not overlap but one ends right before the other starts.
+	#[must_use]
+	pub fn is_adjacent(self, other: Self) -> bool {
+		self.end.checked_add(1) == Some(other.start) || other.end.checked_add(1) == Some(self.start)
+	}
+
+	/// Returns the union of two overlapping or adjacent ranges, or `None` if a gap separates them.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::PortRange;
+	///
+	/// let a = PortRange::new(80, 89).unwrap();
+	/// assert_eq!(a.merge(PortRange::new(90, 99).unwrap()), PortRange::new(80, 99).ok());
+	/// assert_eq!(a.merge(PortRange::single(91)), None);
+	/// ```
+	#[must_use]
+	pub fn merge(self, other: Self) -> Option<Self> {
+		(self.overlaps(other) || self.is_adjacent(other)).then(|| Self {
+			start: self.start.min(other.start),
+			end: self.end.max(other.end),
+		})
+	}
+
+	/// Returns the ports common to both ranges, or `None` if they do not overlap.
+	#[must_use]
+	pub fn intersection(self, other: Self) -> Option<Self> {
+		self.overlaps(other).then(|| Self {
+			start: self.start.max(other.start),
+			end: self.end.min(other.end),
+		})
+	}
+
+	/// Returns the class of every port, or `None` if the range straddles a class boundary.
+	#[must_use]
+	pub fn class(self) -> Option<PortClass> {
+		let class = classify_port(self.start);
+		(class == classify_port(self.end)).then_some(class)
+	}
+
+	/// Returns `true` if the range spans more than one [`PortClass`].
+	#[must_use]
+	pub fn straddles_classes(self) -> bool {
+		self.class().is_none()
+	}
+
+	/// Returns the classes the range touches, in ascending port order.
+	pub fn classes(self) -> impl Iterator<Item = PortClass> {
+		self.split_by_class().map(|(class, _)| class)
+	}
+
+	/// Splits the range at the class boundaries.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::{PortClass, PortRange};
+	///
+	/// let parts: Vec<_> = "1000-1100".parse::<PortRange>().unwrap().split_by_class().collect();
+	/// assert_eq!(
+	///     parts,
+	///     [
+	///         (PortClass::System, "1000-1023".parse().unwrap()),
+	///         (PortClass::User, "1024-1100".parse().unwrap()),
+	///     ]
+	/// );
+	/// ```
+	pub fn split_by_class(self) -> impl Iterator<Item = (PortClass, Self)> {
+		PortClass::ALL.iter().filter_map(move |&class| {
+			let bounds = port_class_range(class);
+			let bounds = Self {
+				start: *bounds.start(),
+				end: *bounds.end(),
+			};
+			self.intersection(bounds).map(|part| (class, part))
+		})
+	}
+
+	/// Returns an iterator over the ports, in ascending order.
+	#[must_use]
+	pub fn iter(self) -> RangeInclusive<u16> {
+		self.start..=self.end
+	}
+}
+
+impl IntoIterator for PortRange {
+	type Item = u16;
+	type IntoIter = RangeInclusive<u16>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl From<u16> for PortRange {
+	fn from(port: u16) -> Self {
+		Self::single(port)
+	}
+}
+
+impl TryFrom<RangeInclusive<u16>> for PortRange {
+	type Error = NetSemError;
+
+	fn try_from(range: RangeInclusive<u16>) -> Result<Self, Self::Error> {
+		Self::new(*range.start(), *range.end())
+	}
+}
+
+impl From<PortRange> for RangeInclusive<u16> {
+	fn from(range: PortRange) -> Self {
+		range.iter()
+	}
+}
+
+impl fmt::Display for PortRange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.start == self.end {
+			write!(f, "{}", self.start)
+		} else {
+			write!(f, "{}-{}", self.start, self.end)
+		}
+	}
+}
+
+impl FromStr for PortRange {
+	type Err = NetSemError;
+
+	/// Parses `start-end`, `start:end` or a single port, all in plain decimal.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let number = |part: &str| {
+			if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+				return Err(NetSemError::InvalidPortRange(s.to_owned()));
+			}
+			part
+				.parse::<u16>()
+				.map_err(|_| NetSemError::PortOutOfRange(part.to_owned()))
+		};
+		match s.split_once(['-', ':']) {
+			Some((start, end)) => Self::new(number(start)?, number(end)?)
+				.map_err(|_| NetSemError::InvalidPortRange(s.to_owned())),
+			None => number(s).map(Self::single),
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PortRange {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PortRange {
+	/// Accepts any string form [`PortRange`] parses, or a bare integer port.
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct PortRangeVisitor;
+
+		impl serde::de::Visitor<'_> for PortRangeVisitor {
+			type Value = PortRange;
+
+			fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				f.write_str("a port or a port range such as \"8000-8100\"")
+			}
+
+			fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<PortRange, E> {
+				u16::try_from(v)
+					.map(PortRange::single)
+					.map_err(|_| E::custom(NetSemError::PortOutOfRange(alloc::format!("{v}"))))
+			}
+
+			fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<PortRange, E> {
+				v.parse().map_err(E::custom)
+			}
+		}
+
+		deserializer.deserialize_any(PortRangeVisitor)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for PortRange {
+	fn schema_name() -> alloc::borrow::Cow<'static, str> {
+		"PortRange".into()
+	}
+
+	fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		schemars::json_schema!({
+			"type": ["string", "integer"],
+			"description": "Inclusive port range such as \"8000-8100\" or \"8000:8100\", or a single port.",
+			"examples": ["8000-8100", "443"]
+		})
+	}
+}
+
+/// Internal helper to check binding for a transport protocol.
 #[cfg(feature = "check")]
-fn check_bind_inner(ip: IpAddr, port: u16, socket_type: socket2::Type) -> Result<(), NetSemError> {
-	use socket2::{Domain, Socket};
+fn check_bind_inner(ip: IpAddr, port: u16, proto: Protocol) -> Result<(), NetSemError> {
+	use socket2::{Domain, Socket, Type};
 
 	let addr = SocketAddr::new(ip, port);
 	let domain = match ip {
 		IpAddr::V4(_) => Domain::IPV4,
 		IpAddr::V6(_) => Domain::IPV6,
 	};
+	let socket_type = match proto.canonical() {
+		Protocol::Tcp | Protocol::Sctp => Type::STREAM,
+		Protocol::Udp | Protocol::UdpLite => Type::DGRAM,
+		_ => return Err(NetSemError::UnsupportedTransport(proto)),
+	};
+	let protocol = socket2::Protocol::from(i32::from(proto.number()));
 
-	let socket = Socket::new(domain, socket_type, None).map_err(|e| NetSemError::BindFailed {
-		addr: addr.to_string(),
-		source: e,
-	})?;
+	let socket =
+		Socket::new(domain, socket_type, Some(protocol)).map_err(|e| NetSemError::BindFailed {
+			addr: addr.to_string(),
+			source: e,
+		})?;
 
 	socket
 		.set_reuse_address(true)
@@ -115,16 +1137,31 @@ fn check_bind_inner(ip: IpAddr, port: u16, socket_type: socket2::Type) -> Result
 	Ok(())
 }
 
+/// Checks if a socket of the given transport can bind to the specified IP and port.
+///
+/// TCP, UDP, SCTP (one-to-one style) and UDP-Lite are supported.
+///
+/// # Returns
+///
+/// * `Ok(())` if the bind succeeds.
+/// * `Err(NetSemError::UnsupportedTransport)` for DCCP.
+/// * `Err(NetSemError::BindFailed)` if the OS refuses the socket or the bind,
+///   including when it lacks the transport.
+#[cfg(feature = "check")]
+pub fn check_bind(ip: IpAddr, port: TransportPort) -> Result<(), NetSemError> {
+	check_bind_inner(ip, port.port, port.proto)
+}
+
 /// Checks if a TCP socket can bind to the specified IP and port.
 #[cfg(feature = "check")]
 pub fn check_bind_tcp(ip: IpAddr, port: u16) -> Result<(), NetSemError> {
-	check_bind_inner(ip, port, socket2::Type::STREAM)
+	check_bind_inner(ip, port, Protocol::Tcp)
 }
 
 /// Checks if a UDP socket can bind to the specified IP and port.
 #[cfg(feature = "check")]
 pub fn check_bind_udp(ip: IpAddr, port: u16) -> Result<(), NetSemError> {
-	check_bind_inner(ip, port, socket2::Type::DGRAM)
+	check_bind_inner(ip, port, Protocol::Udp)
 }
 
 /// Checks if a TCP connection can be established to the specified IP and port.
@@ -212,6 +1249,14 @@ mod tests {
 		assert_eq!(classify_port(65535), PortClass::Dynamic);
 	}
 
+	#[test]
+	fn test_port_class_str_round_trip() {
+		for &class in PortClass::ALL {
+			assert_eq!(class.to_string().parse::<PortClass>().unwrap(), class);
+		}
+		assert!("System".parse::<PortClass>().is_err());
+	}
+
 	#[test]
 	fn test_validate_port() {
 		assert!(validate_port(0).is_err());
@@ -225,6 +1270,241 @@ mod tests {
 		assert!(validate_port_or_zero(80).is_ok());
 	}
 
+	#[test]
+	fn test_port_newtypes() {
+		assert_eq!(Port::new(0), None);
+		assert_eq!(PrivilegedPort::new(1).unwrap().get(), 1);
+		assert_eq!(
+			PrivilegedPort::new(1023).unwrap().class(),
+			PortClass::System
+		);
+		assert_eq!(PrivilegedPort::new(1024), None);
+		assert_eq!(PrivilegedPort::new(0), None);
+		assert_eq!(
+			EphemeralPort::new(65535).unwrap().class(),
+			PortClass::Dynamic
+		);
+		assert_eq!(EphemeralPort::new(49151), None);
+		for &class in PortClass::ALL {
+			let range = port_class_range(class);
+			assert_eq!(AnyPort::new(*range.start()).class(), class);
+			assert_eq!(AnyPort::new(*range.end()).class(), class);
+		}
+
+		let port = Port::try_from(443).unwrap();
+		assert_eq!(PrivilegedPort::try_from(port).unwrap().to_string(), "443");
+		assert!(matches!(
+			EphemeralPort::try_from(port).unwrap_err(),
+			NetSemError::PortNotInRange {
+				port: 443,
+				min: 49152,
+				max: 65535
+			}
+		));
+		assert_eq!(
+			PrivilegedPort::try_from(0).unwrap_err().to_string(),
+			"Port 0 is outside 1-1023"
+		);
+		assert_eq!(u16::from(AnyPort::from(port)), 443);
+		assert!(matches!(
+			"0".parse::<Port>().unwrap_err(),
+			NetSemError::InvalidPort(0)
+		));
+		assert!(matches!(
+			"+1".parse::<AnyPort>().unwrap_err(),
+			NetSemError::SignedPort(_)
+		));
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn test_port_newtypes_serde() {
+		assert_eq!(
+			serde_json::to_string(&Port::new(22).unwrap()).unwrap(),
+			"22"
+		);
+		assert_eq!(serde_json::from_str::<Port>("22").unwrap().get(), 22);
+		assert_eq!(serde_json::from_str::<Port>("\"ssh\"").unwrap().get(), 22);
+		assert_eq!(
+			serde_json::from_str::<AnyPort>("0").unwrap(),
+			AnyPort::new(0)
+		);
+		assert!(serde_json::from_str::<Port>("0").is_err());
+		assert!(serde_json::from_str::<AnyPort>("65536").is_err());
+		assert!(serde_json::from_str::<AnyPort>("-1").is_err());
+		assert!(serde_json::from_str::<PrivilegedPort>("8080").is_err());
+		assert!(serde_json::from_str::<EphemeralPort>("\"50000\"").is_ok());
+	}
+
+	#[test]
+	fn test_parse_port() {
+		assert_eq!(parse_port("0").unwrap(), 0);
+		assert_eq!(parse_port("0080").unwrap(), 80);
+		assert_eq!(parse_port("65535").unwrap(), 65535);
+		assert_eq!(parse_port("HTTP").unwrap(), 80);
+		assert_eq!(parse_port("syslog").unwrap(), 514);
+		assert_eq!(parse_port("8080/tcp").unwrap(), 8080);
+		assert!(matches!(parse_port(""), Err(NetSemError::EmptyPort)));
+		assert!(matches!(parse_port("/tcp"), Err(NetSemError::EmptyPort)));
+		assert!(matches!(parse_port("-1"), Err(NetSemError::SignedPort(_))));
+		assert!(matches!(
+			parse_port("80\n"),
+			Err(NetSemError::PortWhitespace(_))
+		));
+		assert!(matches!(
+			parse_port("8 0"),
+			Err(NetSemError::PortWhitespace(_))
+		));
+		assert!(matches!(
+			parse_port("99999999999999999999"),
+			Err(NetSemError::PortOutOfRange(_))
+		));
+		assert!(matches!(
+			parse_port("no-such-service"),
+			Err(NetSemError::UnknownService(_))
+		));
+		assert!(matches!(
+			parse_port("80/bogus"),
+			Err(NetSemError::InvalidProtocol(_))
+		));
+		assert!(matches!(
+			parse_port("80/icmp"),
+			Err(NetSemError::InvalidTransportPort(_))
+		));
+	}
+
+	#[test]
+	fn test_parse_port_spec() {
+		let sctp = |port| TransportPort::new(Protocol::Sctp, port).unwrap();
+		assert_eq!(
+			parse_port_spec("https/sctp", Protocol::Tcp).unwrap(),
+			sctp(443)
+		);
+		assert_eq!(
+			parse_port_spec("domain", Protocol::Udp).unwrap(),
+			TransportPort::udp(53)
+		);
+		assert!(matches!(
+			parse_port_spec("domain", Protocol::Sctp),
+			Err(NetSemError::UnknownService(_))
+		));
+	}
+
+	#[test]
+	fn test_port_range_parse() {
+		assert_eq!("80".parse::<PortRange>().unwrap(), PortRange::single(80));
+		assert_eq!("0-65535".parse::<PortRange>().unwrap(), PortRange::ALL);
+		assert_eq!(PortRange::ALL.len(), 65536);
+		assert_eq!("5:5".parse::<PortRange>().unwrap().to_string(), "5");
+		for bad in [
+			"", "-", "80-", ":80", "20-10", "+80", "80 - 90", "1-2-3", "a-b",
+		] {
+			assert!(
+				matches!(
+					bad.parse::<PortRange>(),
+					Err(NetSemError::InvalidPortRange(_))
+				),
+				"{bad:?}"
+			);
+		}
+		assert!(matches!(
+			"1-70000".parse::<PortRange>(),
+			Err(NetSemError::PortOutOfRange(_))
+		));
+		assert!(PortRange::try_from(RangeInclusive::new(10, 9)).is_err());
+		assert_eq!(
+			RangeInclusive::from(PortRange::try_from(1..=3).unwrap()),
+			1..=3
+		);
+	}
+
+	#[test]
+	fn test_port_range_set_ops() {
+		let range = |s: &str| s.parse::<PortRange>().unwrap();
+		assert_eq!(range("1-3").into_iter().collect::<Vec<_>>(), [1, 2, 3]);
+		assert_eq!(range("65534-65535").iter().count(), 2);
+		assert!(range("1-10").overlaps(range("10-20")));
+		assert!(!range("1-10").overlaps(range("11-20")));
+		assert!(range("1-10").is_adjacent(range("11-20")));
+		assert!(range("11-20").is_adjacent(range("1-10")));
+		assert!(!range("0-65535").is_adjacent(range("0")));
+		assert!(!range("1-10").is_adjacent(range("5-20")));
+		assert_eq!(range("1-10").merge(range("5-20")), Some(range("1-20")));
+		assert_eq!(range("1-10").merge(range("12-20")), None);
+		assert_eq!(
+			range("1-10").intersection(range("5-20")),
+			Some(range("5-10"))
+		);
+		assert_eq!(range("1-10").intersection(range("11-20")), None);
+		assert!(range("1-10").contains_range(range("2-9")));
+		assert!(!range("1-10").contains_range(range("2-11")));
+	}
+
+	#[test]
+	fn test_port_range_classes() {
+		let range = |s: &str| s.parse::<PortRange>().unwrap();
+		assert_eq!(range("0-1023").class(), Some(PortClass::System));
+		assert_eq!(range("49152-65535").class(), Some(PortClass::Dynamic));
+		assert!(range("1023-1024").straddles_classes());
+		assert_eq!(
+			range("40000-50000").classes().collect::<Vec<_>>(),
+			[PortClass::User, PortClass::Dynamic]
+		);
+		let parts: Vec<_> = PortRange::ALL.split_by_class().collect();
+		assert_eq!(parts.len(), 3);
+		assert_eq!(parts[2], (PortClass::Dynamic, range("49152-65535")));
+	}
+
+	#[test]
+	fn test_transport_port_parse() {
+		assert_eq!(
+			"53/udp".parse::<TransportPort>().unwrap(),
+			TransportPort::udp(53)
+		);
+		assert_eq!(
+			"8080/TCP".parse::<TransportPort>().unwrap(),
+			TransportPort::tcp(8080)
+		);
+		assert_eq!(
+			"0/sctp".parse::<TransportPort>().unwrap().to_string(),
+			"0/sctp"
+		);
+		for bad in [
+			"53",
+			"/udp",
+			"53/",
+			"+53/udp",
+			"65536/tcp",
+			"53/icmp",
+			"53/bogus",
+			"53/udp/x",
+		] {
+			assert!(bad.parse::<TransportPort>().is_err(), "{bad:?}");
+		}
+		assert!(matches!(
+			"53/bogus".parse::<TransportPort>().unwrap_err(),
+			NetSemError::InvalidProtocol(_)
+		));
+		assert!(matches!(
+			TransportPort::new(Protocol::Gre, 1).unwrap_err(),
+			NetSemError::InvalidTransportPort(_)
+		));
+		assert_eq!(TransportPort::tcp(60000).class(), PortClass::Dynamic);
+	}
+
+	#[test]
+	#[cfg(feature = "check")]
+	fn test_check_bind_transport() {
+		use std::net::{IpAddr, Ipv4Addr};
+		let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+		assert!(check_bind(ip, TransportPort::udp(0)).is_ok());
+		assert!(check_bind(ip, TransportPort::new(Protocol::Other(6), 0).unwrap()).is_ok());
+		assert!(matches!(
+			check_bind(ip, TransportPort::new(Protocol::Dccp, 0).unwrap()).unwrap_err(),
+			NetSemError::UnsupportedTransport(Protocol::Dccp)
+		));
+	}
+
 	#[test]
 	#[cfg(feature = "check")]
 	fn test_check_bind_ephemeral() {
@@ -0,0 +1,599 @@
+/* src/port_set.rs */
+
+use crate::error::NetSemError;
+use crate::port::{PortClass, PortRange, TransportPort, port_class_range};
+use crate::proto::Protocol;
+use crate::services;
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+/// Built-in port groups, as port lists without protocol prefixes.
+const GROUPS: &[(&str, &str)] = &[
+	// Nmap's 100 most frequently open TCP ports.
+	(
+		"top-100",
+		"7,9,13,21-23,25-26,37,53,79-81,88,106,110-111,113,119,135,139,143-144,179,199,389,427,\
+		 443-445,465,513-515,543-544,548,554,587,631,646,873,990,993,995,1025-1029,1110,1433,1720,\
+		 1723,1755,1900,2000-2001,2049,2121,2717,3000,3128,3306,3389,3986,4899,5000,5009,5051,5060,\
+		 5101,5190,5357,5432,5631,5666,5800,5900,6000-6001,6646,7070,8000,8008-8009,8080-8081,8443,\
+		 8888,9100,9999-10000,32768,49152-49157",
+	),
+	("web", "80,443,8000,8008,8080,8443"),
+	("mail", "25,110,143,465,587,993,995"),
+	("db", "1433,1521,3306,5432,6379,9042,27017"),
+	("remote", "22,23,3389,5900"),
+];
+
+/// Returns the ports of a named group, or `None` if no group has that name.
+///
+/// The groups are `top-100` (nmap's most frequently open TCP ports), `web`,
+/// `mail`, `db`, `remote`, and the [`PortClass`] names `system`, `user` and
+/// `dynamic`, which cover the ranges from [`port_class_range`].
+///
+/// # Examples
+///
+/// ```
+/// use netsem::port_set::port_group;
+///
+/// assert_eq!(port_group("top-100").unwrap().iter().map(|r| r.len()).sum::<u32>(), 100);
+/// assert_eq!(port_group("system").unwrap()[0].to_string(), "0-1023");
+/// assert!(port_group("nope").is_none());
+/// ```
+#[must_use]
+pub fn port_group(name: &str) -> Option<Vec<PortRange>> {
+	if let Ok(class) = name.parse::<PortClass>() {
+		let range = port_class_range(class);
+		return PortRange::try_from(range)
+			.ok()
+			.map(|range| alloc::vec![range]);
+	}
+	let (_, ports) = GROUPS.iter().find(|(group, _)| *group == name)?;
+	ports.split(',').map(|p| p.parse().ok()).collect()
+}
+
+/// Returns the names accepted by [`port_group`].
+pub fn port_group_names() -> impl Iterator<Item = &'static str> {
+	GROUPS
+		.iter()
+		.map(|&(name, _)| name)
+		.chain(PortClass::ALL.iter().map(|class| class.as_str()))
+}
+
+/// A set of transport-qualified ports, parsed from nmap-style port lists.
+///
+/// Ports are kept per protocol as sorted, merged [`PortRange`]s. The string
+/// form (used by `Display`, `FromStr` and serde) is a comma-separated list
+/// where `T:`, `U:` and `S:` (or a protocol keyword such as `dccp:`) switch
+/// the protocol for the items that follow. An item is a port, a range
+/// (`8000-8100`, `-1024` from port 1, `60000-` to 65535, `-` for 1-65535), a
+/// [group](port_group) or a service name. Items before the first prefix use
+/// TCP; [`PortSet::parse`] takes other defaults. `Display` always writes
+/// prefixes, so the output reads back the same under any default.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{PortSet, Protocol, TransportPort};
+///
+/// let set: PortSet = "22,80,443,8000-8100,U:53,T:25".parse().unwrap();
+/// assert!(set.contains(TransportPort::tcp(8080)));
+/// assert!(set.contains(TransportPort::udp(53)));
+/// assert!(!set.contains(TransportPort::udp(22)));
+/// assert_eq!(set.to_string(), "T:22,25,80,443,8000-8100,U:53");
+///
+/// let web: PortSet = "web".parse().unwrap();
+/// assert_eq!((&set - &web).to_string(), "T:22,25,8001-8007,8009-8079,8081-8100,U:53");
+///
+/// let both = PortSet::parse("domain,ntp", &[Protocol::Tcp, Protocol::Udp]).unwrap();
+/// assert_eq!(both.to_string(), "T:53,123,U:53,123");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PortSet {
+	ranges: BTreeMap<Protocol, Vec<PortRange>>,
+}
+
+impl PortSet {
+	/// Creates an empty set.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Parses a port list, applying items before the first prefix to every protocol in `defaults`.
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPortSet)` for empty items, unknown prefixes,
+	///   or a default protocol without ports.
+	/// * `Err(NetSemError::UnknownService)` for names that are neither a group
+	///   nor a service registered for the current protocol.
+	/// * The [`PortRange`] errors for malformed ranges.
+	pub fn parse(s: &str, defaults: &[Protocol]) -> Result<Self, NetSemError> {
+		if s.chars().any(char::is_whitespace) {
+			return Err(NetSemError::PortWhitespace(s.to_owned()));
+		}
+		let mut set = Self::new();
+		if s.is_empty() {
+			return Ok(set);
+		}
+		if let Some(proto) = defaults.iter().find(|proto| !proto.has_ports()) {
+			return Err(NetSemError::InvalidPortSet(alloc::format!("{proto}:")));
+		}
+		let mut current = defaults.to_vec();
+		for raw in s.split(',') {
+			let mut item = raw;
+			if let Some((prefix, rest)) = raw.split_once(':')
+				&& !prefix.bytes().all(|b| b.is_ascii_digit())
+			{
+				current = alloc::vec![parse_prefix(prefix)?];
+				item = rest;
+			}
+			if item.is_empty() {
+				return Err(NetSemError::InvalidPortSet(raw.to_owned()));
+			}
+			if item.starts_with(|c: char| c.is_ascii_digit() || c == '-') {
+				let range = parse_item_range(item)?;
+				for &proto in &current {
+					set.push_range(proto, range);
+				}
+			} else if let Some(group) = port_group(item) {
+				for &proto in &current {
+					for &range in &group {
+						set.push_range(proto, range);
+					}
+				}
+			} else {
+				for &proto in &current {
+					let port = services::service_ports(item)
+						.find(|p| p.proto() == proto)
+						.ok_or_else(|| NetSemError::UnknownService(raw.to_owned()))?;
+					set.insert(port);
+				}
+			}
+		}
+		set.normalize();
+		Ok(set)
+	}
+
+	/// Adds one port.
+	pub fn insert(&mut self, port: TransportPort) {
+		self.insert_normalized(port.proto(), PortRange::single(port.port()));
+	}
+
+	/// Adds a range of ports for a protocol.
+	///
+	/// # Returns
+	///
+	/// * `Ok(())` once the range is added.
+	/// * `Err(NetSemError::InvalidTransportPort)` if `proto` has no ports.
+	pub fn insert_range(&mut self, proto: Protocol, range: PortRange) -> Result<(), NetSemError> {
+		TransportPort::new(proto, range.start())?;
+		self.insert_normalized(proto, range);
+		Ok(())
+	}
+
+	/// Adds a range for a protocol already known to carry ports and re-merges that protocol.
+	fn insert_normalized(&mut self, proto: Protocol, range: PortRange) {
+		let ranges = self.ranges.entry(proto.canonical()).or_default();
+		ranges.push(range);
+		normalize(ranges);
+	}
+
+	/// Appends a range without merging; callers finish with [`PortSet::normalize`].
+	fn push_range(&mut self, proto: Protocol, range: PortRange) {
+		self
+			.ranges
+			.entry(proto.canonical())
+			.or_default()
+			.push(range);
+	}
+
+	/// Sorts and merges the ranges of every protocol.
+	fn normalize(&mut self) {
+		for ranges in self.ranges.values_mut() {
+			normalize(ranges);
+		}
+	}
+
+	/// Returns `true` if the set holds the port.
+	#[must_use]
+	pub fn contains(&self, port: TransportPort) -> bool {
+		self
+			.ranges(port.proto())
+			.binary_search_by(|range| {
+				if range.end() < port.port() {
+					core::cmp::Ordering::Less
+				} else if range.start() > port.port() {
+					core::cmp::Ordering::Greater
+				} else {
+					core::cmp::Ordering::Equal
+				}
+			})
+			.is_ok()
+	}
+
+	/// Returns the sorted, merged ranges for a protocol.
+	#[must_use]
+	pub fn ranges(&self, proto: Protocol) -> &[PortRange] {
+		self.ranges.get(&proto).map_or(&[], Vec::as_slice)
+	}
+
+	/// Returns the protocols with at least one port, in protocol-number order.
+	pub fn protocols(&self) -> impl Iterator<Item = Protocol> + '_ {
+		self.ranges.keys().copied()
+	}
+
+	/// Returns `true` if the set holds no ports.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.ranges.is_empty()
+	}
+
+	/// Returns the number of (protocol, port) pairs in the set.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self
+			.ranges
+			.values()
+			.flatten()
+			.map(|range| range.len() as usize)
+			.sum()
+	}
+
+	/// Returns every port in the set, by protocol and then port.
+	pub fn iter(&self) -> impl Iterator<Item = TransportPort> + '_ {
+		self.ranges.iter().flat_map(|(&proto, ranges)| {
+			ranges.iter().flat_map(move |range| {
+				range
+					.iter()
+					.map(move |port| TransportPort::new_unchecked(proto, port))
+			})
+		})
+	}
+
+	/// Returns the ports in either set.
+	#[must_use]
+	pub fn union(&self, other: &Self) -> Self {
+		let mut set = self.clone();
+		for (&proto, ranges) in &other.ranges {
+			for &range in ranges {
+				set.push_range(proto, range);
+			}
+		}
+		set.normalize();
+		set
+	}
+
+	/// Returns the ports in both sets.
+	#[must_use]
+	pub fn intersection(&self, other: &Self) -> Self {
+		let mut set = Self::new();
+		for (&proto, ranges) in &self.ranges {
+			for &a in ranges {
+				for &b in other.ranges(proto) {
+					if let Some(range) = a.intersection(b) {
+						set.push_range(proto, range);
+					}
+				}
+			}
+		}
+		set.normalize();
+		set
+	}
+
+	/// Returns the ports in this set but not in `other`.
+	#[must_use]
+	pub fn difference(&self, other: &Self) -> Self {
+		let mut set = Self::new();
+		for (&proto, ranges) in &self.ranges {
+			let mut remaining = ranges.clone();
+			for &cut in other.ranges(proto) {
+				remaining = remaining
+					.into_iter()
+					.flat_map(|range| subtract(range, cut))
+					.collect();
+			}
+			if !remaining.is_empty() {
+				set.ranges.insert(proto, remaining);
+			}
+		}
+		set
+	}
+}
+
+/// Parses a protocol prefix: `T`, `U`, `S`, or any protocol with ports.
+fn parse_prefix(prefix: &str) -> Result<Protocol, NetSemError> {
+	let proto = match prefix {
+		"T" | "t" => Protocol::Tcp,
+		"U" | "u" => Protocol::Udp,
+		"S" | "s" => Protocol::Sctp,
+		_ => prefix
+			.parse::<Protocol>()
+			.map_err(|_| NetSemError::InvalidPortSet(alloc::format!("{prefix}:")))?,
+	};
+	if !proto.has_ports() {
+		return Err(NetSemError::InvalidPortSet(alloc::format!("{prefix}:")));
+	}
+	Ok(proto)
+}
+
+/// Parses a numeric item, including nmap's open-ended `-N`, `N-` and `-` forms.
+fn parse_item_range(item: &str) -> Result<PortRange, NetSemError> {
+	if item == "-" {
+		return PortRange::new(1, u16::MAX);
+	}
+	if let Some(end) = item.strip_prefix('-') {
+		return alloc::format!("1-{end}").parse();
+	}
+	if let Some(start) = item.strip_suffix('-') {
+		return alloc::format!("{start}-{}", u16::MAX).parse();
+	}
+	item.parse()
+}
+
+/// Sorts and merges overlapping or adjacent ranges in place.
+fn normalize(ranges: &mut Vec<PortRange>) {
+	ranges.sort_unstable();
+	let mut merged: Vec<PortRange> = Vec::with_capacity(ranges.len());
+	for &range in ranges.iter() {
+		match merged
+			.last_mut()
+			.and_then(|last| last.merge(range).map(|m| (last, m)))
+		{
+			Some((last, m)) => *last = m,
+			None => merged.push(range),
+		}
+	}
+	*ranges = merged;
+}
+
+/// Returns the parts of `range` outside `cut`.
+fn subtract(range: PortRange, cut: PortRange) -> Vec<PortRange> {
+	if !range.overlaps(cut) {
+		return alloc::vec![range];
+	}
+	let mut parts = Vec::new();
+	if range.start() < cut.start() {
+		parts.extend(PortRange::new(range.start(), cut.start() - 1));
+	}
+	if cut.end() < range.end() {
+		parts.extend(PortRange::new(cut.end() + 1, range.end()));
+	}
+	parts
+}
+
+impl core::ops::BitOr for &PortSet {
+	type Output = PortSet;
+
+	fn bitor(self, rhs: Self) -> PortSet {
+		self.union(rhs)
+	}
+}
+
+impl core::ops::BitAnd for &PortSet {
+	type Output = PortSet;
+
+	fn bitand(self, rhs: Self) -> PortSet {
+		self.intersection(rhs)
+	}
+}
+
+impl core::ops::Sub for &PortSet {
+	type Output = PortSet;
+
+	fn sub(self, rhs: Self) -> PortSet {
+		self.difference(rhs)
+	}
+}
+
+impl Extend<TransportPort> for PortSet {
+	fn extend<I: IntoIterator<Item = TransportPort>>(&mut self, iter: I) {
+		for port in iter {
+			self.push_range(port.proto(), PortRange::single(port.port()));
+		}
+		self.normalize();
+	}
+}
+
+impl FromIterator<TransportPort> for PortSet {
+	fn from_iter<I: IntoIterator<Item = TransportPort>>(iter: I) -> Self {
+		let mut set = Self::new();
+		set.extend(iter);
+		set
+	}
+}
+
+impl fmt::Display for PortSet {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut first = true;
+		for (&proto, ranges) in &self.ranges {
+			for (i, range) in ranges.iter().enumerate() {
+				if !first {
+					f.write_str(",")?;
+				}
+				first = false;
+				if i == 0 {
+					match proto.canonical() {
+						Protocol::Tcp => f.write_str("T:")?,
+						Protocol::Udp => f.write_str("U:")?,
+						Protocol::Sctp => f.write_str("S:")?,
+						other => write!(f, "{other}:")?,
+					}
+				}
+				write!(f, "{range}")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for PortSet {
+	type Err = NetSemError;
+
+	/// Parses a port list, applying unprefixed items to TCP.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s, &[Protocol::Tcp])
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PortSet {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PortSet {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+		s.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for PortSet {
+	fn schema_name() -> alloc::borrow::Cow<'static, str> {
+		"PortSet".into()
+	}
+
+	fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "Nmap-style port list such as \"22,80,8000-8100,U:53\"; unprefixed items are TCP.",
+			"examples": ["22,80,443,8000-8100,U:53", "top-100"]
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloc::string::ToString;
+
+	fn set(s: &str) -> PortSet {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn test_parse_items() {
+		let ports = set("22,80,443,8000-8100,U:53,T:25");
+		assert_eq!(ports.ranges(Protocol::Tcp).len(), 5);
+		assert_eq!(ports.ranges(Protocol::Udp), [PortRange::single(53)]);
+		assert_eq!(ports.len(), 4 + 101 + 1);
+
+		assert_eq!(set("-3").to_string(), "T:1-3");
+		assert_eq!(set("65534-").to_string(), "T:65534-65535");
+		assert_eq!(set("-").to_string(), "T:1-65535");
+		assert_eq!(set("U:-").len(), 65535);
+		assert_eq!(set("0").to_string(), "T:0");
+		assert_eq!(set("1:3,u:5").to_string(), "T:1-3,U:5");
+		assert_eq!(set("S:ssh,dccp:9").to_string(), "dccp:9,S:22");
+		assert_eq!(set("ssh,https").to_string(), "T:22,443");
+		assert_eq!(set("").len(), 0);
+		assert_eq!(set("dynamic").len(), 16384);
+		assert_eq!(set("top-100").len(), 100);
+		assert_eq!(set("U:top-100").ranges(Protocol::Tcp), []);
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		for bad in ["22,", ",22", "22,,80", "T:", "X:22", "icmp:1", "22, 80"] {
+			assert!(bad.parse::<PortSet>().is_err(), "{bad:?}");
+		}
+		assert!(matches!(
+			"syslog".parse::<PortSet>(),
+			Err(NetSemError::UnknownService(_))
+		));
+		assert!(matches!(
+			PortSet::parse("22", &[Protocol::Icmp]),
+			Err(NetSemError::InvalidPortSet(_))
+		));
+		assert!(matches!(
+			"1-70000".parse::<PortSet>(),
+			Err(NetSemError::PortOutOfRange(_))
+		));
+		assert!(matches!(
+			"20-10".parse::<PortSet>(),
+			Err(NetSemError::InvalidPortRange(_))
+		));
+		assert!(PortSet::parse("syslog", &[Protocol::Udp]).is_ok());
+	}
+
+	#[test]
+	fn test_compact_display_round_trips() {
+		let ports = set("80,81,82,79,100-200,150-250,251,U:1,2,S:9");
+		assert_eq!(ports.to_string(), "T:79-82,100-251,U:1-2,S:9");
+		assert_eq!(
+			PortSet::parse(&ports.to_string(), &[Protocol::Udp]).unwrap(),
+			ports
+		);
+
+		let mut raw = PortSet::default();
+		raw
+			.insert_range(Protocol::Other(6), PortRange::single(22))
+			.unwrap();
+		raw
+			.insert_range(Protocol::Other(17), PortRange::single(53))
+			.unwrap();
+		assert_eq!(raw.to_string(), "T:22,U:53");
+		assert!(matches!(
+			raw
+				.insert_range(Protocol::Icmp, PortRange::ALL)
+				.unwrap_err(),
+			NetSemError::InvalidTransportPort(_)
+		));
+	}
+
+	#[test]
+	fn test_set_operations() {
+		let a = set("1-100,U:53");
+		let b = set("50-150,U:53-54");
+		assert_eq!((&a | &b).to_string(), "T:1-150,U:53-54");
+		assert_eq!((&a & &b).to_string(), "T:50-100,U:53");
+		assert_eq!((&a - &b).to_string(), "T:1-49");
+		assert_eq!((&b - &a).to_string(), "T:101-150,U:54");
+		assert_eq!(&set("1-10") - &set("3,5-6"), set("1-2,4,7-10"));
+		assert!((&a - &a).is_empty());
+		assert_eq!(&set("0-65535") - &set("0,65535"), set("1-65534"));
+	}
+
+	#[test]
+	fn test_membership_and_iteration() {
+		let ports = set("10-12,20,U:5");
+		for port in [10, 11, 12, 20] {
+			assert!(ports.contains(TransportPort::tcp(port)));
+		}
+		for port in [9, 13, 19, 21] {
+			assert!(!ports.contains(TransportPort::tcp(port)));
+		}
+		assert!(!ports.contains(TransportPort::udp(10)));
+		let all: Vec<_> = ports.iter().collect();
+		assert_eq!(all.len(), 5);
+		assert_eq!(all[4], TransportPort::udp(5));
+		assert_eq!(all.iter().rev().copied().collect::<PortSet>(), ports);
+		let mut extended = ports.clone();
+		extended.extend([13, 9, 11, 21].map(TransportPort::tcp));
+		assert_eq!(extended.to_string(), "T:9-13,20-21,U:5");
+		assert_eq!(
+			ports.protocols().collect::<Vec<_>>(),
+			[Protocol::Tcp, Protocol::Udp]
+		);
+	}
+
+	#[test]
+	fn test_groups() {
+		for name in port_group_names() {
+			let group = port_group(name).unwrap();
+			assert!(!group.is_empty(), "{name}");
+			assert!(name.parse::<PortSet>().is_ok(), "{name}");
+		}
+		assert_eq!(
+			port_group("user").unwrap(),
+			[PortRange::new(1024, 49151).unwrap()]
+		);
+	}
+}
@@ -0,0 +1,379 @@
+/* src/proto.rs */
+
+use crate::error::NetSemError;
+use alloc::borrow::ToOwned;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+/// An IP protocol number (the IPv4 Protocol / IPv6 Next Header field).
+///
+/// Numbers with an entry in the IANA "Assigned Internet Protocol Numbers"
+/// registry that commonly appear in configuration have a named variant; all
+/// other numbers are [`Protocol::Other`]. Build values with
+/// `Protocol::from(u8)` so that, for example, 6 always becomes
+/// [`Protocol::Tcp`]. Equality, ordering and hashing compare the numbers.
+///
+/// The string form is the lowercase IANA keyword (`"tcp"`, `"ipv6-icmp"`) or,
+/// for unnamed numbers, the decimal number. Parsing also accepts numbers,
+/// any letter case and the aliases `icmpv6`, `ipip` and `ospf`.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::Protocol;
+///
+/// assert_eq!("udp".parse::<Protocol>().unwrap(), Protocol::Udp);
+/// assert_eq!("132".parse::<Protocol>().unwrap(), Protocol::Sctp);
+/// assert_eq!("ICMPv6".parse::<Protocol>().unwrap().number(), 58);
+/// assert_eq!(Protocol::from(6), Protocol::Tcp);
+/// assert_eq!(Protocol::from(253).to_string(), "253");
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum Protocol {
+	/// IPv6 Hop-by-Hop Option (0).
+	Hopopt,
+	/// Internet Control Message Protocol (1).
+	Icmp,
+	/// Internet Group Management Protocol (2).
+	Igmp,
+	/// IPv4 encapsulation (IP-in-IP) (4).
+	IpInIp,
+	/// Transmission Control Protocol (6).
+	Tcp,
+	/// Exterior Gateway Protocol (8).
+	Egp,
+	/// User Datagram Protocol (17).
+	Udp,
+	/// Datagram Congestion Control Protocol (33).
+	Dccp,
+	/// IPv6 encapsulation (41).
+	Ipv6,
+	/// Routing Header for IPv6 (43).
+	Ipv6Route,
+	/// Fragment Header for IPv6 (44).
+	Ipv6Frag,
+	/// Reservation Protocol (46).
+	Rsvp,
+	/// Generic Routing Encapsulation (47).
+	Gre,
+	/// Encapsulating Security Payload (50).
+	Esp,
+	/// Authentication Header (51).
+	Ah,
+	/// ICMP for IPv6 (58).
+	Icmpv6,
+	/// No Next Header for IPv6 (59).
+	Ipv6NoNxt,
+	/// Destination Options for IPv6 (60).
+	Ipv6Opts,
+	/// Enhanced Interior Gateway Routing Protocol (88).
+	Eigrp,
+	/// Open Shortest Path First (89).
+	Ospf,
+	/// Protocol Independent Multicast (103).
+	Pim,
+	/// Virtual Router Redundancy Protocol (112).
+	Vrrp,
+	/// Layer Two Tunneling Protocol v3 (115).
+	L2tp,
+	/// Stream Control Transmission Protocol (132).
+	Sctp,
+	/// Lightweight User Datagram Protocol (136).
+	UdpLite,
+	/// MPLS in IP (137).
+	MplsInIp,
+	/// Ethernet (143).
+	Ethernet,
+	/// Any other protocol number.
+	Other(u8),
+}
+
+impl Protocol {
+	/// Returns the protocol number.
+	#[must_use]
+	pub fn number(self) -> u8 {
+		match self {
+			Self::Hopopt => 0,
+			Self::Icmp => 1,
+			Self::Igmp => 2,
+			Self::IpInIp => 4,
+			Self::Tcp => 6,
+			Self::Egp => 8,
+			Self::Udp => 17,
+			Self::Dccp => 33,
+			Self::Ipv6 => 41,
+			Self::Ipv6Route => 43,
+			Self::Ipv6Frag => 44,
+			Self::Rsvp => 46,
+			Self::Gre => 47,
+			Self::Esp => 50,
+			Self::Ah => 51,
+			Self::Icmpv6 => 58,
+			Self::Ipv6NoNxt => 59,
+			Self::Ipv6Opts => 60,
+			Self::Eigrp => 88,
+			Self::Ospf => 89,
+			Self::Pim => 103,
+			Self::Vrrp => 112,
+			Self::L2tp => 115,
+			Self::Sctp => 132,
+			Self::UdpLite => 136,
+			Self::MplsInIp => 137,
+			Self::Ethernet => 143,
+			Self::Other(n) => n,
+		}
+	}
+
+	/// Returns the lowercase IANA keyword, or `None` for [`Protocol::Other`] numbers without a variant.
+	#[must_use]
+	pub fn keyword(self) -> Option<&'static str> {
+		match self.canonical() {
+			Self::Hopopt => Some("hopopt"),
+			Self::Icmp => Some("icmp"),
+			Self::Igmp => Some("igmp"),
+			Self::IpInIp => Some("ipv4"),
+			Self::Tcp => Some("tcp"),
+			Self::Egp => Some("egp"),
+			Self::Udp => Some("udp"),
+			Self::Dccp => Some("dccp"),
+			Self::Ipv6 => Some("ipv6"),
+			Self::Ipv6Route => Some("ipv6-route"),
+			Self::Ipv6Frag => Some("ipv6-frag"),
+			Self::Rsvp => Some("rsvp"),
+			Self::Gre => Some("gre"),
+			Self::Esp => Some("esp"),
+			Self::Ah => Some("ah"),
+			Self::Icmpv6 => Some("ipv6-icmp"),
+			Self::Ipv6NoNxt => Some("ipv6-nonxt"),
+			Self::Ipv6Opts => Some("ipv6-opts"),
+			Self::Eigrp => Some("eigrp"),
+			Self::Ospf => Some("ospfigp"),
+			Self::Pim => Some("pim"),
+			Self::Vrrp => Some("vrrp"),
+			Self::L2tp => Some("l2tp"),
+			Self::Sctp => Some("sctp"),
+			Self::UdpLite => Some("udplite"),
+			Self::MplsInIp => Some("mpls-in-ip"),
+			Self::Ethernet => Some("ethernet"),
+			Self::Other(_) => None,
+		}
+	}
+
+	/// Returns the named variant for this number, folding `Other(6)` into `Tcp` and so on.
+	///
+	/// Match on the canonical form whenever an `Other` value may have been
+	/// built by hand rather than through `From<u8>` or `FromStr`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::Protocol;
+	///
+	/// assert!(matches!(Protocol::Other(6).canonical(), Protocol::Tcp));
+	/// assert!(matches!(Protocol::Other(253).canonical(), Protocol::Other(253)));
+	/// ```
+	#[must_use]
+	pub fn canonical(self) -> Self {
+		Self::from(self.number())
+	}
+
+	/// Returns `true` for transports with port numbers (TCP, UDP, DCCP, SCTP, UDP-Lite).
+	#[must_use]
+	pub fn has_ports(self) -> bool {
+		matches!(self.number(), 6 | 17 | 33 | 132 | 136)
+	}
+}
+
+impl From<u8> for Protocol {
+	fn from(n: u8) -> Self {
+		match n {
+			0 => Self::Hopopt,
+			1 => Self::Icmp,
+			2 => Self::Igmp,
+			4 => Self::IpInIp,
+			6 => Self::Tcp,
+			8 => Self::Egp,
+			17 => Self::Udp,
+			33 => Self::Dccp,
+			41 => Self::Ipv6,
+			43 => Self::Ipv6Route,
+			44 => Self::Ipv6Frag,
+			46 => Self::Rsvp,
+			47 => Self::Gre,
+			50 => Self::Esp,
+			51 => Self::Ah,
+			58 => Self::Icmpv6,
+			59 => Self::Ipv6NoNxt,
+			60 => Self::Ipv6Opts,
+			88 => Self::Eigrp,
+			89 => Self::Ospf,
+			103 => Self::Pim,
+			112 => Self::Vrrp,
+			115 => Self::L2tp,
+			132 => Self::Sctp,
+			136 => Self::UdpLite,
+			137 => Self::MplsInIp,
+			143 => Self::Ethernet,
+			n => Self::Other(n),
+		}
+	}
+}
+
+impl From<Protocol> for u8 {
+	fn from(proto: Protocol) -> Self {
+		proto.number()
+	}
+}
+
+impl PartialEq for Protocol {
+	fn eq(&self, other: &Self) -> bool {
+		self.number() == other.number()
+	}
+}
+
+impl Eq for Protocol {}
+
+impl Hash for Protocol {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.number().hash(state);
+	}
+}
+
+impl PartialOrd for Protocol {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Protocol {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.number().cmp(&other.number())
+	}
+}
+
+impl fmt::Display for Protocol {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.keyword() {
+			Some(keyword) => f.write_str(keyword),
+			None => write!(f, "{}", self.number()),
+		}
+	}
+}
+
+impl FromStr for Protocol {
+	type Err = NetSemError;
+
+	/// Parses a keyword, an alias, or a decimal protocol number (0-255).
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+			return s
+				.parse::<u8>()
+				.map(Self::from)
+				.map_err(|_| NetSemError::InvalidProtocol(s.to_owned()));
+		}
+		let alias = match s.to_ascii_lowercase().as_str() {
+			"icmpv6" => Some(Self::Icmpv6),
+			"ipip" => Some(Self::IpInIp),
+			"ospf" => Some(Self::Ospf),
+			_ => None,
+		};
+		alias
+			.or_else(|| {
+				(0..=u8::MAX)
+					.map(Self::from)
+					.find(|p| p.keyword().is_some_and(|k| k.eq_ignore_ascii_case(s)))
+			})
+			.ok_or_else(|| NetSemError::InvalidProtocol(s.to_owned()))
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Protocol {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Protocol {
+	/// Accepts any string form [`Protocol`] parses, or a bare integer.
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct ProtocolVisitor;
+
+		impl serde::de::Visitor<'_> for ProtocolVisitor {
+			type Value = Protocol;
+
+			fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				f.write_str("an IP protocol name such as \"tcp\" or a number from 0 to 255")
+			}
+
+			fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Protocol, E> {
+				u8::try_from(v)
+					.map(Protocol::from)
+					.map_err(|_| E::custom(NetSemError::InvalidProtocol(alloc::format!("{v}"))))
+			}
+
+			fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Protocol, E> {
+				v.parse().map_err(E::custom)
+			}
+		}
+
+		deserializer.deserialize_any(ProtocolVisitor)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Protocol {
+	fn schema_name() -> alloc::borrow::Cow<'static, str> {
+		"Protocol".into()
+	}
+
+	fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		schemars::json_schema!({
+			"type": ["string", "integer"],
+			"description": "IP protocol keyword (e.g. \"tcp\", \"ipv6-icmp\") or number (0-255)."
+		})
+	}
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Protocol {
+	fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+		u8::arbitrary(u).map(Self::from)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_protocol_numbers_round_trip() {
+		for n in 0..=u8::MAX {
+			let proto = Protocol::from(n);
+			assert_eq!(proto.number(), n);
+			assert_eq!(proto.to_string().parse::<Protocol>().unwrap(), proto, "{n}");
+			assert_eq!(n.to_string().parse::<Protocol>().unwrap(), proto);
+		}
+	}
+
+	#[test]
+	fn test_protocol_names() {
+		assert_eq!(Protocol::Icmpv6.to_string(), "ipv6-icmp");
+		assert_eq!("IPV6-ICMP".parse::<Protocol>().unwrap(), Protocol::Icmpv6);
+		assert_eq!("ipip".parse::<Protocol>().unwrap(), Protocol::IpInIp);
+		assert_eq!("ospf".parse::<Protocol>().unwrap(), Protocol::Ospf);
+		assert_eq!(Protocol::Other(6), Protocol::Tcp);
+		assert_eq!(Protocol::Other(6).keyword(), Some("tcp"));
+		assert!(matches!(Protocol::Other(17).canonical(), Protocol::Udp));
+		assert!(Protocol::Other(132).has_ports());
+		for bad in ["", "256", "-1", "tcpp", " tcp"] {
+			assert!(bad.parse::<Protocol>().is_err(), "{bad:?}");
+		}
+		assert!(Protocol::Sctp.has_ports());
+		assert!(!Protocol::Icmp.has_ports());
+	}
+}
@@ -0,0 +1,566 @@
+/* src/range.rs */
+
+use crate::error::NetSemError;
+use crate::ip::{IpClass, IpClassSet, bits_to_ip, class_prefixes, ip_to_bits};
+use crate::net::IpNet;
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::net::IpAddr;
+use core::str::FromStr;
+
+/// An inclusive range of IP addresses of a single family (e.g., `10.0.0.5-10.0.0.20`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IpRange {
+	start: IpAddr,
+	end: IpAddr,
+}
+
+impl IpRange {
+	/// Creates a range from its first and last address.
+	///
+	/// # Returns
+	///
+	/// * `Ok(IpRange)` if both ends share a family and `start <= end`.
+	/// * `Err(NetSemError::InvalidRange)` otherwise.
+	pub fn new(start: IpAddr, end: IpAddr) -> Result<Self, NetSemError> {
+		if start.is_ipv4() != end.is_ipv4() || start > end {
+			return Err(NetSemError::InvalidRange(format!("{start}-{end}")));
+		}
+		Ok(Self { start, end })
+	}
+
+	/// Returns the first address of the range.
+	#[must_use]
+	pub fn start(&self) -> IpAddr {
+		self.start
+	}
+
+	/// Returns the last address of the range.
+	#[must_use]
+	pub fn end(&self) -> IpAddr {
+		self.end
+	}
+
+	/// Checks whether `ip` falls inside the range.
+	#[must_use]
+	pub fn contains(&self, ip: IpAddr) -> bool {
+		ip.is_ipv4() == self.start.is_ipv4() && self.start <= ip && ip <= self.end
+	}
+
+	/// Iterates over every address in the range.
+	#[must_use]
+	pub fn addresses(&self) -> IpAddrIter {
+		IpAddrIter::new(self.start, self.end)
+	}
+
+	/// Returns the smallest list of prefixes that exactly covers the range.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::IpRange;
+	///
+	/// let range: IpRange = "10.0.0.0-10.0.2.255".parse().unwrap();
+	/// let nets: Vec<String> = range.to_prefixes().iter().map(ToString::to_string).collect();
+	/// assert_eq!(nets, ["10.0.0.0/23", "10.0.2.0/24"]);
+	/// ```
+	#[must_use]
+	pub fn to_prefixes(&self) -> Vec<IpNet> {
+		let ipv4 = self.start.is_ipv4();
+		let width: u32 = if ipv4 { 32 } else { 128 };
+		let end = ip_to_bits(self.end);
+		let mut start = ip_to_bits(self.start);
+		let mut out = Vec::new();
+		loop {
+			let mut host_bits = start.trailing_zeros().min(width);
+			while host_bits > 0 && block_last(start, host_bits) > end {
+				host_bits -= 1;
+			}
+			let last = block_last(start, host_bits);
+			out.push(
+				IpNet::new(bits_to_ip(start, ipv4), (width - host_bits) as u8)
+					.expect("prefix length is within the address width"),
+			);
+			if last >= end {
+				return out;
+			}
+			start = last + 1;
+		}
+	}
+}
+
+/// Last address of the aligned block starting at `start` with `host_bits` host bits.
+fn block_last(start: u128, host_bits: u32) -> u128 {
+	start
+		| 1u128
+			.checked_shl(host_bits)
+			.map_or(u128::MAX, |size| size - 1)
+}
+
+impl From<IpNet> for IpRange {
+	fn from(net: IpNet) -> Self {
+		Self {
+			start: net.network(),
+			end: net.last(),
+		}
+	}
+}
+
+impl fmt::Display for IpRange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}-{}", self.start, self.end)
+	}
+}
+
+impl FromStr for IpRange {
+	type Err = NetSemError;
+
+	/// Parses `start-end`, e.g. `10.0.0.5-10.0.0.20` or `2001:db8::1-2001:db8::ff`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || NetSemError::InvalidRange(s.to_owned());
+		let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+		let start = start.parse::<IpAddr>().map_err(|_| invalid())?;
+		let end = end.parse::<IpAddr>().map_err(|_| invalid())?;
+		Self::new(start, end).map_err(|_| invalid())
+	}
+}
+
+impl IpNet {
+	/// Iterates over every address in the prefix, including the network address.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::IpNet;
+	///
+	/// let net: IpNet = "10.0.0.0/30".parse().unwrap();
+	/// assert_eq!(net.addresses().count(), 4);
+	/// assert_eq!(net.addresses().next_back().unwrap().to_string(), "10.0.0.3");
+	/// ```
+	#[must_use]
+	pub fn addresses(&self) -> IpAddrIter {
+		IpAddrIter::new(self.network(), self.last())
+	}
+
+	/// Iterates over the usable host addresses in the prefix.
+	///
+	/// For IPv4 the network and broadcast addresses are skipped, except in
+	/// `/31` and `/32` prefixes (RFC 3021). For IPv6 the Subnet-Router anycast
+	/// address (the network address) is skipped, except in `/127` and `/128`
+	/// prefixes (RFC 6164).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::IpNet;
+	///
+	/// let net: IpNet = "192.168.1.0/24".parse().unwrap();
+	/// let mut hosts = net.hosts();
+	/// assert_eq!(hosts.next().unwrap().to_string(), "192.168.1.1");
+	/// assert_eq!(hosts.next_back().unwrap().to_string(), "192.168.1.254");
+	/// assert_eq!(net.hosts().nth(99).unwrap().to_string(), "192.168.1.100");
+	/// ```
+	#[must_use]
+	pub fn hosts(&self) -> IpAddrIter {
+		let mut iter = self.addresses();
+		let max = self.max_prefix_len();
+		if self.prefix_len() + 1 < max {
+			iter.next();
+			if self.is_ipv4() {
+				iter.next_back();
+			}
+		}
+		iter
+	}
+}
+
+/// A lazy, double-ended iterator over consecutive IP addresses.
+///
+/// `nth` and `nth_back` run in constant time, so `step_by` and `skip` are
+/// cheap even across large IPv6 prefixes.
+#[derive(Debug, Clone)]
+pub struct IpAddrIter {
+	front: u128,
+	back: u128,
+	ipv4: bool,
+	done: bool,
+}
+
+impl IpAddrIter {
+	fn new(start: IpAddr, end: IpAddr) -> Self {
+		Self {
+			front: ip_to_bits(start),
+			back: ip_to_bits(end),
+			ipv4: start.is_ipv4(),
+			done: false,
+		}
+	}
+
+	/// Restricts the iterator to addresses whose [`classify_ip`](crate::classify_ip) result is in `classes`.
+	///
+	/// The remaining range is cut along [`class_prefixes`] up front, so
+	/// non-matching stretches are skipped without being visited: an IPv6
+	/// prefix with no matching address yields `None` at once.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::{IpClass, IpNet};
+	///
+	/// let net: IpNet = "100.0.0.0/8".parse().unwrap();
+	/// let first_shared = net.hosts().only_classes([IpClass::SharedAddress]).next();
+	/// assert_eq!(first_shared.unwrap().to_string(), "100.64.0.0");
+	///
+	/// let doc: IpNet = "2001:db8::/32".parse().unwrap();
+	/// assert!(doc.addresses().only_classes([IpClass::Private]).next().is_none());
+	/// ```
+	#[must_use]
+	pub fn only_classes(
+		self,
+		classes: impl Into<IpClassSet>,
+	) -> impl DoubleEndedIterator<Item = IpAddr> + FusedIterator {
+		let (front, back, ipv4) = (self.front, self.back, self.ipv4);
+		let spans = if self.done {
+			Vec::new()
+		} else {
+			class_spans(classes.into(), ipv4)
+		};
+		spans
+			.into_iter()
+			.filter_map(move |(lo, hi)| {
+				let (lo, hi) = (lo.max(front), hi.min(back));
+				(lo <= hi).then_some(Self {
+					front: lo,
+					back: hi,
+					ipv4,
+					done: false,
+				})
+			})
+			.flatten()
+	}
+
+	/// Returns the number of addresses left, or `None` if it exceeds `u128::MAX`.
+	#[must_use]
+	pub fn remaining(&self) -> Option<u128> {
+		if self.done {
+			Some(0)
+		} else {
+			(self.back - self.front).checked_add(1)
+		}
+	}
+
+	fn ip(&self, bits: u128) -> IpAddr {
+		bits_to_ip(bits, self.ipv4)
+	}
+}
+
+/// Returns the sorted, disjoint address spans of one family that [`classify_ip`](crate::classify_ip) puts in `classes`.
+///
+/// Relies on [`class_prefixes`] covering every non-`Global` address exactly
+/// once; `Global` is whatever lies between them.
+fn class_spans(classes: IpClassSet, ipv4: bool) -> Vec<(u128, u128)> {
+	let mut special: Vec<(u128, u128, IpClass)> = IpClass::ALL
+		.iter()
+		.flat_map(|&class| class_prefixes(class).map(move |net| (net, class)))
+		.filter(|(net, _)| net.is_ipv4() == ipv4)
+		.map(|(net, class)| (ip_to_bits(net.network()), ip_to_bits(net.last()), class))
+		.collect();
+	special.sort_unstable_by_key(|&(lo, ..)| lo);
+	let global = classes.contains(IpClass::Global);
+	let mut spans = Vec::new();
+	let mut cursor = Some(0u128);
+	for (lo, hi, class) in special {
+		if let Some(start) = cursor
+			&& global
+			&& start < lo
+		{
+			spans.push((start, lo - 1));
+		}
+		if classes.contains(class) {
+			spans.push((lo, hi));
+		}
+		cursor = hi.checked_add(1);
+	}
+	let max = if ipv4 {
+		u128::from(u32::MAX)
+	} else {
+		u128::MAX
+	};
+	if let Some(start) = cursor
+		&& global
+		&& start <= max
+	{
+		spans.push((start, max));
+	}
+	spans
+}
+
+impl Iterator for IpAddrIter {
+	type Item = IpAddr;
+
+	fn next(&mut self) -> Option<IpAddr> {
+		if self.done {
+			return None;
+		}
+		let ip = self.ip(self.front);
+		if self.front == self.back {
+			self.done = true;
+		} else {
+			self.front += 1;
+		}
+		Some(ip)
+	}
+
+	fn nth(&mut self, n: usize) -> Option<IpAddr> {
+		if self.done {
+			return None;
+		}
+		match self.front.checked_add(n as u128) {
+			Some(pos) if pos <= self.back => {
+				self.front = pos;
+				self.next()
+			}
+			_ => {
+				self.done = true;
+				None
+			}
+		}
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match self.remaining().and_then(|n| usize::try_from(n).ok()) {
+			Some(n) => (n, Some(n)),
+			None => (usize::MAX, None),
+		}
+	}
+
+	/// Returns the number of addresses left in O(1).
+	///
+	/// # Panics
+	///
+	/// Panics if more than `usize::MAX` addresses remain (e.g. a whole IPv6
+	/// `/64`), as std's `count` does on overflow. Use
+	/// [`IpAddrIter::remaining`] for the exact figure.
+	fn count(self) -> usize {
+		self
+			.remaining()
+			.and_then(|n| usize::try_from(n).ok())
+			.expect("address count overflows usize; use IpAddrIter::remaining")
+	}
+
+	fn last(mut self) -> Option<IpAddr> {
+		self.next_back()
+	}
+}
+
+impl DoubleEndedIterator for IpAddrIter {
+	fn next_back(&mut self) -> Option<IpAddr> {
+		if self.done {
+			return None;
+		}
+		let ip = self.ip(self.back);
+		if self.front == self.back {
+			self.done = true;
+		} else {
+			self.back -= 1;
+		}
+		Some(ip)
+	}
+
+	fn nth_back(&mut self, n: usize) -> Option<IpAddr> {
+		if self.done {
+			return None;
+		}
+		match self.back.checked_sub(n as u128) {
+			Some(pos) if pos >= self.front => {
+				self.back = pos;
+				self.next_back()
+			}
+			_ => {
+				self.done = true;
+				None
+			}
+		}
+	}
+}
+
+impl FusedIterator for IpAddrIter {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn net(s: &str) -> IpNet {
+		s.parse().unwrap()
+	}
+
+	fn strs(iter: impl Iterator<Item = IpAddr>) -> Vec<String> {
+		iter.map(|ip| ip.to_string()).collect()
+	}
+
+	#[test]
+	fn test_hosts() {
+		assert_eq!(strs(net("10.0.0.0/30").hosts()), ["10.0.0.1", "10.0.0.2"]);
+		assert_eq!(strs(net("10.0.0.0/31").hosts()), ["10.0.0.0", "10.0.0.1"]);
+		assert_eq!(strs(net("10.0.0.7/32").hosts()), ["10.0.0.7"]);
+		assert_eq!(
+			strs(net("2001:db8::/126").hosts()),
+			["2001:db8::1", "2001:db8::2", "2001:db8::3"]
+		);
+		assert_eq!(net("10.0.0.0/16").hosts().count(), 65534);
+	}
+
+	#[test]
+	fn test_iter_double_ended() {
+		let mut iter = net("10.0.0.0/29").addresses();
+		assert_eq!(iter.next().unwrap().to_string(), "10.0.0.0");
+		assert_eq!(iter.next_back().unwrap().to_string(), "10.0.0.7");
+		assert_eq!(iter.nth(2).unwrap().to_string(), "10.0.0.3");
+		assert_eq!(iter.nth_back(1).unwrap().to_string(), "10.0.0.5");
+		assert_eq!(strs(iter.by_ref()), ["10.0.0.4"]);
+		assert!(iter.next().is_none());
+		assert!(iter.next_back().is_none());
+	}
+
+	#[test]
+	fn test_iter_large_prefix() {
+		let net = net("2001:db8::/64");
+		let mut iter = net.addresses();
+		assert_eq!(iter.remaining(), Some(1 << 64));
+		assert_eq!(
+			iter.nth(usize::MAX).unwrap().to_string(),
+			"2001:db8::ffff:ffff:ffff:ffff"
+		);
+		assert!(iter.next().is_none());
+
+		let stepped = strs(net.addresses().step_by(1 << 48).take(3));
+		assert_eq!(
+			stepped,
+			["2001:db8::", "2001:db8:0:0:1::", "2001:db8:0:0:2::"]
+		);
+		assert_eq!(self::net("::/0").addresses().remaining(), None);
+		assert_eq!(self::net("2001:db8::/120").addresses().count(), 256);
+		let mut nearly_all = net.addresses();
+		nearly_all.next();
+		assert_eq!(nearly_all.count(), usize::MAX);
+		assert_eq!(
+			self::net("::/0").addresses().last().unwrap().to_string(),
+			"ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "overflows usize")]
+	fn test_count_overflow_panics() {
+		let _ = net("2001:db8::/64").addresses().count();
+	}
+
+	#[test]
+	fn test_only_classes() {
+		let classes = IpClassSet::from(IpClass::Documentation);
+		let found = strs(
+			net("192.0.0.0/16")
+				.addresses()
+				.only_classes(classes)
+				.take(2),
+		);
+		assert_eq!(found, ["192.0.2.0", "192.0.2.1"]);
+		let last = net("192.0.0.0/16")
+			.addresses()
+			.only_classes(classes)
+			.next_back();
+		assert_eq!(last.unwrap().to_string(), "192.0.2.255");
+
+		for (range, classes) in [
+			("192.0.0.0/16", IpClassSet::from(IpClass::Global)),
+			(
+				"192.0.0.0/22",
+				IpClassSet::from([IpClass::Global, IpClass::ProtocolAssignment]),
+			),
+			("169.254.255.0/23", IpClassSet::from(IpClass::LinkLocal)),
+			("255.255.255.0/24", IpClassSet::from(IpClass::Broadcast)),
+			(
+				"0.0.0.0/24",
+				IpClassSet::from([IpClass::Unspecified, IpClass::Global]),
+			),
+			(
+				"fe7f:ffff:ffff:ffff:ffff:ffff:ffff:ff00/120",
+				IpClassSet::from(IpClass::Global),
+			),
+			(
+				"::/120",
+				IpClassSet::from([IpClass::Loopback, IpClass::Unspecified]),
+			),
+			(
+				"ffff:ffff:ffff:ffff:ffff:ffff:ffff:ff00/120",
+				IpClassSet::from(IpClass::Multicast),
+			),
+		] {
+			let filtered: Vec<_> = net(range)
+				.addresses()
+				.filter(|&ip| classes.contains(crate::classify_ip(ip)))
+				.collect();
+			let skipped: Vec<_> = net(range).addresses().only_classes(classes).collect();
+			assert_eq!(skipped, filtered, "{range}");
+			let mut reversed: Vec<_> = net(range).addresses().only_classes(classes).rev().collect();
+			reversed.reverse();
+			assert_eq!(reversed, filtered, "{range}");
+		}
+		assert!(
+			net("2001:db8::/32")
+				.addresses()
+				.only_classes(IpClass::Private)
+				.next()
+				.is_none()
+		);
+		assert_eq!(
+			net("::/0")
+				.addresses()
+				.only_classes(IpClass::Private)
+				.next_back()
+				.unwrap()
+				.to_string(),
+			"fdff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"
+		);
+	}
+
+	#[test]
+	fn test_range() {
+		let range: IpRange = "10.0.0.5-10.0.0.20".parse().unwrap();
+		assert!(range.contains("10.0.0.5".parse().unwrap()));
+		assert!(!range.contains("10.0.0.21".parse().unwrap()));
+		assert_eq!(range.addresses().count(), 16);
+		assert_eq!(range.to_string(), "10.0.0.5-10.0.0.20");
+		assert_eq!(
+			range
+				.to_prefixes()
+				.iter()
+				.map(ToString::to_string)
+				.collect::<Vec<_>>(),
+			[
+				"10.0.0.5/32",
+				"10.0.0.6/31",
+				"10.0.0.8/29",
+				"10.0.0.16/30",
+				"10.0.0.20/32"
+			]
+		);
+		assert!(matches!(
+			"10.0.0.9-10.0.0.1".parse::<IpRange>().unwrap_err(),
+			NetSemError::InvalidRange(_)
+		));
+		assert!("10.0.0.1-::1".parse::<IpRange>().is_err());
+
+		let all: IpRange = "::-ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"
+			.parse()
+			.unwrap();
+		assert_eq!(all.to_prefixes(), [net("::/0")]);
+		assert_eq!(
+			IpRange::from(net("10.0.0.0/8")).to_prefixes(),
+			[net("10.0.0.0/8")]
+		);
+	}
+}
@@ -0,0 +1,572 @@
+/* src/rir.rs */
+
+use crate::asn::Asn;
+use crate::error::NetSemError;
+use crate::net::{IpNet, PrefixTable};
+use crate::range::IpRange;
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use core::ops::RangeInclusive;
+use core::str::FromStr;
+
+/// The version line at the top of a delegated-statistics file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+	/// Format version (e.g., `2`).
+	pub version: String,
+	/// Registry name (e.g., `apnic`).
+	pub registry: String,
+	/// Serial number of the file.
+	pub serial: String,
+	/// Number of records, excluding the header and summaries.
+	pub records: u64,
+	/// Start date of the covered period (`YYYYMMDD`).
+	pub start_date: String,
+	/// End date of the covered period (`YYYYMMDD`).
+	pub end_date: String,
+	/// Offset from UTC (e.g., `+1000`).
+	pub utc_offset: String,
+}
+
+/// The kind of number resource a record delegates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ResourceKind {
+	/// Autonomous System Numbers.
+	Asn,
+	/// IPv4 addresses.
+	Ipv4,
+	/// IPv6 addresses.
+	Ipv6,
+}
+
+impl ResourceKind {
+	/// Returns the name used in the file (`asn`, `ipv4` or `ipv6`).
+	#[must_use]
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Asn => "asn",
+			Self::Ipv4 => "ipv4",
+			Self::Ipv6 => "ipv6",
+		}
+	}
+}
+
+impl fmt::Display for ResourceKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for ResourceKind {
+	type Err = NetSemError;
+
+	/// Parses the name produced by [`ResourceKind::as_str`].
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"asn" => Ok(Self::Asn),
+			"ipv4" => Ok(Self::Ipv4),
+			"ipv6" => Ok(Self::Ipv6),
+			_ => Err(NetSemError::UnknownName {
+				kind: "resource kind",
+				name: s.to_owned(),
+			}),
+		}
+	}
+}
+
+/// A per-type summary line (`registry|*|type|*|count|summary`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeSummary {
+	/// The resource type counted.
+	pub kind: ResourceKind,
+	/// Number of records of that type.
+	pub count: u64,
+}
+
+/// The delegation status of a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DelegationStatus {
+	/// Allocated to a LIR or NIR for further assignment.
+	Allocated,
+	/// Assigned to an end user.
+	Assigned,
+	/// Held by the registry and available for delegation (extended files only).
+	Available,
+	/// Reserved by the registry (extended files only).
+	Reserved,
+}
+
+impl DelegationStatus {
+	/// Returns the name used in the file.
+	#[must_use]
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Allocated => "allocated",
+			Self::Assigned => "assigned",
+			Self::Available => "available",
+			Self::Reserved => "reserved",
+		}
+	}
+}
+
+impl fmt::Display for DelegationStatus {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for DelegationStatus {
+	type Err = NetSemError;
+
+	/// Parses the name produced by [`DelegationStatus::as_str`].
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"allocated" => Ok(Self::Allocated),
+			"assigned" => Ok(Self::Assigned),
+			"available" => Ok(Self::Available),
+			"reserved" => Ok(Self::Reserved),
+			_ => Err(NetSemError::UnknownName {
+				kind: "delegation status",
+				name: s.to_owned(),
+			}),
+		}
+	}
+}
+
+/// The number resources covered by one record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resource {
+	/// A block of ASNs.
+	Asn(RangeInclusive<Asn>),
+	/// IP prefixes. IPv4 records give an address count, which is split into
+	/// CIDR prefixes when it is not a power of two or not aligned.
+	Ip(Vec<IpNet>),
+}
+
+impl Resource {
+	/// Returns the resource type.
+	#[must_use]
+	pub fn kind(&self) -> ResourceKind {
+		match self {
+			Self::Asn(_) => ResourceKind::Asn,
+			Self::Ip(nets) if nets.first().is_some_and(IpNet::is_ipv6) => ResourceKind::Ipv6,
+			Self::Ip(_) => ResourceKind::Ipv4,
+		}
+	}
+}
+
+/// One delegation record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delegation {
+	/// Registry that made the delegation (e.g., `ripencc`).
+	pub registry: String,
+	/// ISO 3166 country code; `ZZ` or empty for undelegated space.
+	pub country: String,
+	/// The delegated resources.
+	pub resource: Resource,
+	/// Date of the delegation (`YYYYMMDD`), if recorded.
+	pub date: Option<String>,
+	/// Delegation status.
+	pub status: DelegationStatus,
+	/// Opaque holder ID (extended files only).
+	pub opaque_id: Option<String>,
+}
+
+/// A parsed delegated-statistics file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DelegatedStats {
+	/// The version line, if present.
+	pub header: Option<Header>,
+	/// The per-type summary lines.
+	pub summaries: Vec<TypeSummary>,
+	/// The delegation records, in file order.
+	pub records: Vec<Delegation>,
+}
+
+/// Parses a `delegated-<rir>-[extended-]latest` file.
+///
+/// Comment lines (`#`) and blank lines are skipped.
+///
+/// # Returns
+///
+/// * `Err(NetSemError::InvalidDelegation)` with the 1-based line number of the
+///   first malformed line.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::rir::{Resource, parse_delegated};
+///
+/// let stats = parse_delegated(
+///     "2|apnic|20240101|2|19830613|20231231|+1000\n\
+///      apnic|*|ipv4|*|1|summary\n\
+///      apnic|AU|ipv4|1.0.0.0|768|20110811|assigned|A91872ED\n\
+///      apnic|JP|asn|173|2|20020801|allocated|A91EF3A1\n",
+/// )
+/// .unwrap();
+/// assert_eq!(stats.header.unwrap().registry, "apnic");
+/// let Resource::Ip(nets) = &stats.records[0].resource else { unreachable!() };
+/// let nets: Vec<String> = nets.iter().map(ToString::to_string).collect();
+/// assert_eq!(nets, ["1.0.0.0/23", "1.0.2.0/24"]);
+/// ```
+pub fn parse_delegated(input: &str) -> Result<DelegatedStats, NetSemError> {
+	let mut stats = DelegatedStats::default();
+	for (index, line) in input.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let err = |reason: String| NetSemError::InvalidDelegation {
+			line: index + 1,
+			reason,
+		};
+		let fields: Vec<&str> = line.split('|').collect();
+		let is_version = fields[0].bytes().all(|b| b.is_ascii_digit() || b == b'.');
+		if is_version {
+			if stats.header.is_some() || !stats.records.is_empty() {
+				return Err(err("unexpected version line".to_owned()));
+			}
+			stats.header = Some(parse_header(&fields).map_err(err)?);
+		} else if fields.get(5) == Some(&"summary") {
+			stats.summaries.push(parse_summary(&fields).map_err(err)?);
+		} else {
+			stats.records.push(parse_record(&fields).map_err(err)?);
+		}
+	}
+	Ok(stats)
+}
+
+/// Reads and parses a delegated-statistics file from disk.
+///
+/// See [`parse_delegated`] for the format.
+#[cfg(feature = "std")]
+pub fn load_delegated(path: impl AsRef<std::path::Path>) -> Result<DelegatedStats, NetSemError> {
+	let path = path.as_ref();
+	let input = std::fs::read_to_string(path).map_err(|e| NetSemError::ReadFailed {
+		path: path.display().to_string(),
+		source: e,
+	})?;
+	parse_delegated(&input)
+}
+
+fn parse_header(fields: &[&str]) -> Result<Header, String> {
+	let [
+		version,
+		registry,
+		serial,
+		records,
+		start_date,
+		end_date,
+		utc_offset,
+	] = fields
+	else {
+		return Err(format!(
+			"expected 7 fields in version line, found {}",
+			fields.len()
+		));
+	};
+	Ok(Header {
+		version: (*version).to_owned(),
+		registry: (*registry).to_owned(),
+		serial: (*serial).to_owned(),
+		records: number(records, "record count")?,
+		start_date: (*start_date).to_owned(),
+		end_date: (*end_date).to_owned(),
+		utc_offset: (*utc_offset).to_owned(),
+	})
+}
+
+fn parse_summary(fields: &[&str]) -> Result<TypeSummary, String> {
+	let [_, "*", kind, "*", count, "summary"] = fields else {
+		return Err("malformed summary line".to_owned());
+	};
+	Ok(TypeSummary {
+		kind: kind
+			.parse()
+			.map_err(|_| format!("unknown resource type {kind:?}"))?,
+		count: number(count, "summary count")?,
+	})
+}
+
+fn parse_record(fields: &[&str]) -> Result<Delegation, String> {
+	if !(7..=9).contains(&fields.len()) {
+		return Err(format!("expected 7 to 9 fields, found {}", fields.len()));
+	}
+	let (start, value) = (fields[3], fields[4]);
+	let kind: ResourceKind = fields[2]
+		.parse()
+		.map_err(|_| format!("unknown resource type {:?}", fields[2]))?;
+	let resource = match kind {
+		ResourceKind::Asn => {
+			let first: u32 = number(start, "ASN")?;
+			let count: u32 = number(value, "ASN count")?;
+			let last = count
+				.checked_sub(1)
+				.and_then(|n| first.checked_add(n))
+				.ok_or_else(|| format!("invalid ASN block {start}+{value}"))?;
+			Resource::Asn(Asn::new(first)..=Asn::new(last))
+		}
+		ResourceKind::Ipv4 => {
+			let first: Ipv4Addr = start
+				.parse()
+				.map_err(|_| format!("invalid IPv4 address {start:?}"))?;
+			let count: u32 = number(value, "address count")?;
+			let last = count
+				.checked_sub(1)
+				.and_then(|n| u32::from(first).checked_add(n))
+				.ok_or_else(|| format!("invalid IPv4 block {start}+{value}"))?;
+			let range = IpRange::new(IpAddr::V4(first), IpAddr::V4(Ipv4Addr::from(last)))
+				.map_err(|e| e.to_string())?;
+			Resource::Ip(range.to_prefixes())
+		}
+		ResourceKind::Ipv6 => {
+			let first: Ipv6Addr = start
+				.parse()
+				.map_err(|_| format!("invalid IPv6 address {start:?}"))?;
+			let len: u8 = number(value, "prefix length")?;
+			let net = IpNet::new(IpAddr::V6(first), len).map_err(|e| e.to_string())?;
+			if net.network() != IpAddr::V6(first) {
+				return Err(format!("{start}/{len} has host bits set"));
+			}
+			Resource::Ip(vec![net])
+		}
+	};
+	let date = match fields[5] {
+		"" | "00000000" => None,
+		date => Some(date.to_owned()),
+	};
+	Ok(Delegation {
+		registry: fields[0].to_owned(),
+		country: fields[1].to_owned(),
+		resource,
+		date,
+		status: fields[6]
+			.parse()
+			.map_err(|_| format!("unknown status {:?}", fields[6]))?,
+		opaque_id: fields
+			.get(7)
+			.filter(|id| !id.is_empty())
+			.map(|id| (*id).to_owned()),
+	})
+}
+
+fn number<T: FromStr>(s: &str, what: &str) -> Result<T, String> {
+	if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+		return Err(format!("invalid {what} {s:?}"));
+	}
+	s.parse().map_err(|_| format!("invalid {what} {s:?}"))
+}
+
+/// Address and ASN lookups over delegation records, possibly from several registries.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::rir::{RirIndex, parse_delegated};
+///
+/// let stats = parse_delegated(
+///     "ripencc|NL|ipv4|193.0.0.0|2048|19930901|assigned\n\
+///      ripencc|NL|asn|3333|1|19930901|assigned\n\
+///      ripencc|NL|ipv6|2001:67c:2e8::|48|20080128|assigned\n",
+/// )
+/// .unwrap();
+/// let index: RirIndex = stats.records.into_iter().collect();
+///
+/// assert_eq!(index.lookup_ip("193.0.7.1".parse().unwrap()).unwrap().country, "NL");
+/// assert_eq!(index.lookup_asn("AS3333".parse().unwrap()).unwrap().registry, "ripencc");
+/// assert!(index.lookup_ip("2001:db8::1".parse().unwrap()).is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RirIndex {
+	records: Vec<Delegation>,
+	prefixes: PrefixTable<usize>,
+	asns: BTreeMap<Asn, (Asn, usize)>,
+}
+
+impl RirIndex {
+	/// Creates an empty index.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the record covering `ip`, preferring the most specific prefix.
+	#[must_use]
+	pub fn lookup_ip(&self, ip: IpAddr) -> Option<&Delegation> {
+		self.prefixes.lookup(ip).map(|(_, &i)| &self.records[i])
+	}
+
+	/// Returns the record whose ASN block contains `asn`.
+	#[must_use]
+	pub fn lookup_asn(&self, asn: Asn) -> Option<&Delegation> {
+		let (_, &(last, i)) = self.asns.range(..=asn).next_back()?;
+		(asn <= last).then(|| &self.records[i])
+	}
+
+	/// Returns all indexed records, in insertion order.
+	#[must_use]
+	pub fn records(&self) -> &[Delegation] {
+		&self.records
+	}
+}
+
+impl Extend<Delegation> for RirIndex {
+	fn extend<I: IntoIterator<Item = Delegation>>(&mut self, iter: I) {
+		for record in iter {
+			let i = self.records.len();
+			match &record.resource {
+				Resource::Asn(range) => {
+					self.asns.insert(*range.start(), (*range.end(), i));
+				}
+				Resource::Ip(nets) => {
+					for net in nets {
+						self.prefixes.insert(*net, i);
+					}
+				}
+			}
+			self.records.push(record);
+		}
+	}
+}
+
+impl FromIterator<Delegation> for RirIndex {
+	fn from_iter<I: IntoIterator<Item = Delegation>>(iter: I) -> Self {
+		let mut index = Self::new();
+		index.extend(iter);
+		index
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const APNIC: &str = "\
+# comment
+2|apnic|20240101|5|19830613|20231231|+1000
+apnic|*|asn|*|2|summary
+apnic|*|ipv4|*|2|summary
+apnic|*|ipv6|*|1|summary
+apnic|JP|asn|173|1|20020801|allocated|A91EF3A1
+apnic|AU|asn|4608|1024|20000131|allocated|A9142D08
+apnic|AU|ipv4|1.0.0.0|256|20110811|assigned|A91872ED
+apnic|CN|ipv4|1.0.1.0|768|20110414|allocated|A92E1062
+
+apnic|ZZ|ipv6|2001:db8::|32||reserved|
+";
+
+	#[test]
+	fn test_parse_delegated() {
+		let stats = parse_delegated(APNIC).unwrap();
+		let header = stats.header.as_ref().unwrap();
+		assert_eq!(header.version, "2");
+		assert_eq!(header.records, 5);
+		assert_eq!(stats.summaries.len(), 3);
+		assert_eq!(
+			stats.summaries[1],
+			TypeSummary {
+				kind: ResourceKind::Ipv4,
+				count: 2
+			}
+		);
+		assert_eq!(stats.records.len(), 5);
+
+		let asn = &stats.records[1];
+		assert_eq!(asn.resource, Resource::Asn(Asn::new(4608)..=Asn::new(5631)));
+		assert_eq!(asn.status, DelegationStatus::Allocated);
+		assert_eq!(asn.opaque_id.as_deref(), Some("A9142D08"));
+
+		let cn = &stats.records[3];
+		assert_eq!(cn.resource.kind(), ResourceKind::Ipv4);
+		let Resource::Ip(nets) = &cn.resource else {
+			panic!("expected prefixes");
+		};
+		let nets: Vec<String> = nets.iter().map(ToString::to_string).collect();
+		assert_eq!(nets, ["1.0.1.0/24", "1.0.2.0/23"]);
+
+		let reserved = &stats.records[4];
+		assert_eq!(reserved.resource.kind(), ResourceKind::Ipv6);
+		assert_eq!(reserved.date, None);
+		assert_eq!(reserved.opaque_id, None);
+		assert_eq!(reserved.status, DelegationStatus::Reserved);
+	}
+
+	#[test]
+	fn test_name_errors() {
+		assert!(matches!(
+			"ipv5".parse::<ResourceKind>(),
+			Err(NetSemError::UnknownName {
+				kind: "resource kind",
+				..
+			})
+		));
+		let err = "leased".parse::<DelegationStatus>().unwrap_err();
+		assert_eq!(err.to_string(), "Unknown delegation status: leased");
+	}
+
+	#[test]
+	fn test_errors_carry_line_numbers() {
+		for (input, line) in [
+			("apnic|AU|ipv4|1.0.0.0|0|20110811|assigned", 1),
+			("# c\napnic|AU|ipv4|1.0.0.x|256|20110811|assigned", 2),
+			("\n\napnic|AU|ipv4|255.255.255.0|512|20110811|assigned", 3),
+			("apnic|AU|ipv6|2001:db8::1|32|20110811|assigned", 1),
+			("apnic|AU|ipv6|2001:db8::|129|20110811|assigned", 1),
+			("apnic|AU|asn|4294967295|2|20110811|assigned", 1),
+			("apnic|AU|ipx|1.0.0.0|256|20110811|assigned", 1),
+			("apnic|AU|ipv4|1.0.0.0|256|20110811|leased", 1),
+			("apnic|AU|ipv4|1.0.0.0|256", 1),
+			("apnic|*|ipv4|*|x|summary", 1),
+			("2|apnic|1|2", 1),
+			("apnic|AU|asn|1|1|20110811|assigned\n2|apnic|1|2|3|4|5", 2),
+		] {
+			match parse_delegated(input) {
+				Err(NetSemError::InvalidDelegation { line: l, .. }) => assert_eq!(l, line, "{input}"),
+				other => panic!("{input}: {other:?}"),
+			}
+		}
+	}
+
+	#[test]
+	fn test_index_lookup() {
+		let index: RirIndex = parse_delegated(APNIC)
+			.unwrap()
+			.records
+			.into_iter()
+			.collect();
+		assert_eq!(index.records().len(), 5);
+		assert_eq!(
+			index.lookup_ip("1.0.0.1".parse().unwrap()).unwrap().country,
+			"AU"
+		);
+		assert_eq!(
+			index
+				.lookup_ip("1.0.3.255".parse().unwrap())
+				.unwrap()
+				.country,
+			"CN"
+		);
+		assert!(index.lookup_ip("1.0.4.0".parse().unwrap()).is_none());
+		assert_eq!(
+			index
+				.lookup_ip("2001:db8::1".parse().unwrap())
+				.unwrap()
+				.country,
+			"ZZ"
+		);
+
+		assert_eq!(index.lookup_asn(Asn::new(173)).unwrap().country, "JP");
+		assert_eq!(index.lookup_asn(Asn::new(5631)).unwrap().country, "AU");
+		assert!(index.lookup_asn(Asn::new(5632)).is_none());
+		assert!(index.lookup_asn(Asn::new(174)).is_none());
+		assert!(index.lookup_asn(Asn::new(1)).is_none());
+	}
+}
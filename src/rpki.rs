@@ -0,0 +1,511 @@
+/* src/rpki.rs */
+
+use crate::asn::Asn;
+use crate::error::NetSemError;
+use crate::net::{IpNet, PrefixTable};
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+/// A Route Origin Authorization: `asn` may originate `prefix` and its
+/// more-specifics down to `max_length`.
+///
+/// Deserialization goes through [`Roa::new`], so a `maxLength` outside the
+/// prefix length and the family maximum is rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", try_from = "RawRoa")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Roa {
+	/// The authorized origin AS.
+	pub asn: Asn,
+	/// The authorized prefix.
+	pub prefix: IpNet,
+	/// The longest prefix length the origin may announce.
+	pub max_length: u8,
+	/// The trust anchor the ROA was validated under (e.g., `ripe`), if exported.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub ta: Option<String>,
+}
+
+impl Roa {
+	/// Creates a ROA without a trust anchor label.
+	///
+	/// # Returns
+	///
+	/// * `Err(NetSemError::InvalidPrefix)` if `max_length` is shorter than the
+	///   prefix or longer than the address family allows.
+	pub fn new(prefix: IpNet, max_length: u8, asn: Asn) -> Result<Self, NetSemError> {
+		if max_length < prefix.prefix_len() || max_length > prefix.max_prefix_len() {
+			return Err(NetSemError::InvalidPrefix(format!(
+				"{prefix} with maxLength {max_length}"
+			)));
+		}
+		Ok(Self {
+			asn,
+			prefix,
+			max_length,
+			ta: None,
+		})
+	}
+
+	/// Checks whether the ROA authorizes `origin` to announce `route` (RFC 6811 "matched").
+	///
+	/// ROAs for AS0 never match (RFC 6483 section 4).
+	#[must_use]
+	pub fn matches(&self, route: &IpNet, origin: Asn) -> bool {
+		self.covers(route)
+			&& self.asn.get() != 0
+			&& self.asn == origin
+			&& route.prefix_len() <= self.max_length
+	}
+
+	/// Checks whether the ROA prefix covers `route` (RFC 6811 "covered").
+	#[must_use]
+	pub fn covers(&self, route: &IpNet) -> bool {
+		self.prefix.contains_net(route)
+	}
+}
+
+/// Unvalidated ROA fields, checked by [`Roa::new`] on deserialization.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(
+	feature = "schemars",
+	derive(schemars::JsonSchema),
+	schemars(rename = "Roa")
+)]
+struct RawRoa {
+	/// The authorized origin AS.
+	asn: Asn,
+	/// The authorized prefix.
+	prefix: IpNet,
+	/// The longest prefix length the origin may announce.
+	max_length: u8,
+	/// The trust anchor the ROA was validated under (e.g., `ripe`), if exported.
+	#[serde(default)]
+	ta: Option<String>,
+}
+
+impl TryFrom<RawRoa> for Roa {
+	type Error = NetSemError;
+
+	fn try_from(raw: RawRoa) -> Result<Self, Self::Error> {
+		let mut roa = Self::new(raw.prefix, raw.max_length, raw.asn)?;
+		roa.ta = raw.ta;
+		Ok(roa)
+	}
+}
+
+/// Export formats for validated ROA payloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RoaFormat {
+	/// JSON as exported by Routinator, rpki-client, Fort and OctoRPKI:
+	/// `{"roas": [{"asn": "AS13335", "prefix": "1.1.1.0/24", "maxLength": 24, "ta": "apnic"}]}`.
+	/// The ASN may also be a bare number.
+	Json,
+	/// CSV with columns `ASN,IP Prefix,Max Length[,Trust Anchor]`, as exported by
+	/// Routinator and rpki-client. A header row, blank lines and `#` comments
+	/// are skipped; an empty max length defaults to the prefix length.
+	Csv,
+}
+
+impl RoaFormat {
+	fn name(self) -> &'static str {
+		match self {
+			Self::Json => "ROA JSON",
+			Self::Csv => "ROA CSV",
+		}
+	}
+}
+
+/// Parses exported ROAs.
+///
+/// # Returns
+///
+/// * `Ok(Vec<Roa>)` in input order.
+/// * `Err(NetSemError::InvalidFeed)` if the document or any ROA is malformed.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::rpki::{RoaFormat, parse_roas};
+///
+/// let roas = parse_roas(RoaFormat::Csv, "ASN,IP Prefix,Max Length,Trust Anchor\nAS13335,1.1.1.0/24,24,apnic\n").unwrap();
+/// assert_eq!(roas[0].asn.get(), 13335);
+/// assert_eq!(roas[0].ta.as_deref(), Some("apnic"));
+/// ```
+pub fn parse_roas(format: RoaFormat, input: &str) -> Result<Vec<Roa>, NetSemError> {
+	match format {
+		RoaFormat::Json => parse_json(input),
+		RoaFormat::Csv => parse_csv(input),
+	}
+}
+
+/// Reads and parses exported ROAs from a local file.
+///
+/// See [`RoaFormat`] for the accepted formats.
+pub fn load_roas(format: RoaFormat, path: impl AsRef<Path>) -> Result<Vec<Roa>, NetSemError> {
+	let path = path.as_ref();
+	let input = std::fs::read_to_string(path).map_err(|e| NetSemError::ReadFailed {
+		path: path.display().to_string(),
+		source: e,
+	})?;
+	parse_roas(format, &input)
+}
+
+/// Route origin validation state (RFC 6811).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum RouteState {
+	/// A covering ROA authorizes the origin and prefix length.
+	Valid,
+	/// A covering ROA authorizes the origin, but not at this prefix length.
+	InvalidLength,
+	/// Covering ROAs exist, but none authorizes the origin.
+	InvalidOrigin,
+	/// No ROA covers the prefix.
+	NotFound,
+}
+
+impl RouteState {
+	/// Returns `true` for both invalid states.
+	#[must_use]
+	pub fn is_invalid(self) -> bool {
+		matches!(self, Self::InvalidLength | Self::InvalidOrigin)
+	}
+
+	/// Returns the stable kebab-case name of the state.
+	#[must_use]
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Valid => "valid",
+			Self::InvalidLength => "invalid-length",
+			Self::InvalidOrigin => "invalid-origin",
+			Self::NotFound => "not-found",
+		}
+	}
+}
+
+impl fmt::Display for RouteState {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// The outcome of validating one announcement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validation<'a> {
+	/// The validation state.
+	pub state: RouteState,
+	/// Every ROA covering the announced prefix, most specific first.
+	pub covering: Vec<&'a Roa>,
+}
+
+/// A set of ROAs indexed for route origin validation.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::rpki::{RoaFormat, RoaTable, RouteState, parse_roas};
+///
+/// let roas = parse_roas(RoaFormat::Json, r#"{"roas": [
+///     {"asn": "AS13335", "prefix": "1.1.1.0/24", "maxLength": 24, "ta": "apnic"}
+/// ]}"#).unwrap();
+/// let table: RoaTable = roas.into_iter().collect();
+///
+/// let route = "1.1.1.0/24".parse().unwrap();
+/// assert_eq!(table.validate(&route, "AS13335".parse().unwrap()).state, RouteState::Valid);
+/// assert_eq!(table.validate(&route, "AS64500".parse().unwrap()).state, RouteState::InvalidOrigin);
+///
+/// let more_specific = "1.1.1.0/25".parse().unwrap();
+/// let outcome = table.validate(&more_specific, "AS13335".parse().unwrap());
+/// assert_eq!(outcome.state, RouteState::InvalidLength);
+/// assert_eq!(outcome.covering.len(), 1);
+///
+/// let unrelated = "192.0.2.0/24".parse().unwrap();
+/// assert_eq!(table.validate(&unrelated, "AS13335".parse().unwrap()).state, RouteState::NotFound);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RoaTable {
+	table: PrefixTable<Vec<Roa>>,
+	len: usize,
+}
+
+impl RoaTable {
+	/// Creates an empty table.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the number of ROAs in the table.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Returns `true` if the table holds no ROAs.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Iterates over the ROAs covering `route`, most specific prefix first.
+	pub fn covering<'a>(&'a self, route: &IpNet) -> impl Iterator<Item = &'a Roa> + 'a {
+		let len = route.prefix_len();
+		self
+			.table
+			.matches(route.network())
+			.filter(move |(net, _)| net.prefix_len() <= len)
+			.flat_map(|(_, roas)| roas)
+	}
+
+	/// Validates an announcement of `route` originated by `origin` (RFC 6811 section 2).
+	#[must_use]
+	pub fn validate(&self, route: &IpNet, origin: Asn) -> Validation<'_> {
+		let covering: Vec<&Roa> = self.covering(route).collect();
+		let state = if covering.is_empty() {
+			RouteState::NotFound
+		} else if covering.iter().any(|roa| roa.matches(route, origin)) {
+			RouteState::Valid
+		} else if covering
+			.iter()
+			.any(|roa| roa.asn == origin && roa.asn.get() != 0)
+		{
+			RouteState::InvalidLength
+		} else {
+			RouteState::InvalidOrigin
+		};
+		Validation { state, covering }
+	}
+}
+
+impl Extend<Roa> for RoaTable {
+	fn extend<I: IntoIterator<Item = Roa>>(&mut self, iter: I) {
+		for roa in iter {
+			self.len += 1;
+			if let Some(roas) = self.table.get_mut(&roa.prefix) {
+				roas.push(roa);
+			} else {
+				self.table.insert(roa.prefix, vec![roa]);
+			}
+		}
+	}
+}
+
+impl FromIterator<Roa> for RoaTable {
+	fn from_iter<I: IntoIterator<Item = Roa>>(iter: I) -> Self {
+		let mut table = Self::new();
+		table.extend(iter);
+		table
+	}
+}
+
+#[derive(Deserialize)]
+struct JsonExport {
+	roas: Vec<JsonRoa>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonRoa {
+	asn: Asn,
+	prefix: String,
+	max_length: Option<u8>,
+	ta: Option<String>,
+}
+
+fn invalid(format: RoaFormat, reason: String) -> NetSemError {
+	NetSemError::InvalidFeed {
+		provider: format.name(),
+		reason,
+	}
+}
+
+fn build_roa(
+	prefix: &str,
+	max_length: Option<u8>,
+	asn: Asn,
+	ta: Option<String>,
+) -> Result<Roa, String> {
+	let prefix: IpNet = prefix
+		.trim()
+		.parse()
+		.map_err(|_| format!("invalid prefix {prefix:?}"))?;
+	let mut roa =
+		Roa::new(prefix, max_length.unwrap_or(prefix.prefix_len()), asn).map_err(|e| e.to_string())?;
+	roa.ta = ta.filter(|ta| !ta.is_empty());
+	Ok(roa)
+}
+
+fn parse_json(input: &str) -> Result<Vec<Roa>, NetSemError> {
+	let export: JsonExport =
+		serde_json::from_str(input).map_err(|e| invalid(RoaFormat::Json, e.to_string()))?;
+	export
+		.roas
+		.into_iter()
+		.map(|r| {
+			build_roa(&r.prefix, r.max_length, r.asn, r.ta)
+				.map_err(|reason| invalid(RoaFormat::Json, reason))
+		})
+		.collect()
+}
+
+fn parse_csv(input: &str) -> Result<Vec<Roa>, NetSemError> {
+	let mut out = Vec::new();
+	for (index, line) in input.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let at_line = |reason: String| invalid(RoaFormat::Csv, format!("line {}: {reason}", index + 1));
+		let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+		if out.is_empty() && fields[0].eq_ignore_ascii_case("asn") {
+			continue;
+		}
+		if !(3..=4).contains(&fields.len()) {
+			return Err(at_line(format!(
+				"expected 3 or 4 columns, found {}",
+				fields.len()
+			)));
+		}
+		let asn: Asn = fields[0]
+			.parse()
+			.map_err(|e: NetSemError| at_line(e.to_string()))?;
+		let max_length = match fields[2] {
+			"" => None,
+			s => Some(
+				s.parse::<u8>()
+					.map_err(|_| at_line(format!("invalid max length {s:?}")))?,
+			),
+		};
+		let ta = fields.get(3).map(|s| (*s).to_owned());
+		out.push(build_roa(fields[1], max_length, asn, ta).map_err(at_line)?);
+	}
+	Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const JSON: &str = r#"{
+		"metadata": {"generated": 1700000000},
+		"roas": [
+			{"asn": "AS13335", "prefix": "1.0.0.0/24", "maxLength": 24, "ta": "apnic"},
+			{"asn": 13335, "prefix": "2606:4700::/32", "maxLength": 48, "ta": "arin"},
+			{"asn": "AS0", "prefix": "192.0.2.0/24", "maxLength": 32, "ta": "apnic"},
+			{"asn": "AS64500", "prefix": "10.0.0.0/8", "maxLength": 16, "ta": "ripe"},
+			{"asn": "AS64501", "prefix": "10.1.0.0/16", "maxLength": 24, "ta": "ripe"}
+		]
+	}"#;
+
+	fn net(s: &str) -> IpNet {
+		s.parse().unwrap()
+	}
+
+	fn asn(n: u32) -> Asn {
+		Asn::new(n)
+	}
+
+	#[test]
+	fn test_parse_json() {
+		let roas = parse_roas(RoaFormat::Json, JSON).unwrap();
+		assert_eq!(roas.len(), 5);
+		assert_eq!(roas[1].asn, asn(13335));
+		assert_eq!(roas[1].max_length, 48);
+		assert!(
+			parse_roas(
+				RoaFormat::Json,
+				r#"{"roas": [{"asn": 1, "prefix": "10.0.0.0/8", "maxLength": 7}]}"#
+			)
+			.is_err()
+		);
+		assert!(parse_roas(RoaFormat::Json, "[]").is_err());
+	}
+
+	#[test]
+	fn test_parse_csv() {
+		let input = "ASN,IP Prefix,Max Length,Trust Anchor\nAS13335,1.0.0.0/24,24,apnic\n\n# comment\n64500,10.0.0.0/8,,ripe\nAS1.10,2001:db8::/32,48\n";
+		let roas = parse_roas(RoaFormat::Csv, input).unwrap();
+		assert_eq!(roas.len(), 3);
+		assert_eq!(roas[1].max_length, 8);
+		assert_eq!(roas[2].asn, asn(65546));
+		assert_eq!(roas[2].ta, None);
+
+		let err = parse_roas(RoaFormat::Csv, "AS1,10.0.0.0/8,8\nAS2,10.0.0.0/8,33\n").unwrap_err();
+		assert!(err.to_string().contains("line 2"), "{err}");
+		assert!(parse_roas(RoaFormat::Csv, "AS1,10.0.0.0/8").is_err());
+		assert!(parse_roas(RoaFormat::Csv, "ASX,10.0.0.0/8,8").is_err());
+	}
+
+	#[test]
+	fn test_validate() {
+		let table: RoaTable = parse_roas(RoaFormat::Json, JSON)
+			.unwrap()
+			.into_iter()
+			.collect();
+		assert_eq!(table.len(), 5);
+
+		let cases = [
+			("1.0.0.0/24", 13335, RouteState::Valid),
+			("1.0.0.0/25", 13335, RouteState::InvalidLength),
+			("1.0.0.0/24", 64500, RouteState::InvalidOrigin),
+			("1.0.0.0/23", 13335, RouteState::NotFound),
+			("2606:4700:10::/48", 13335, RouteState::Valid),
+			("192.0.2.0/24", 0, RouteState::InvalidOrigin),
+			("10.2.0.0/16", 64500, RouteState::Valid),
+			("10.1.2.0/24", 64501, RouteState::Valid),
+			("10.1.2.0/24", 64500, RouteState::InvalidLength),
+			("10.1.0.0/16", 64500, RouteState::Valid),
+			("203.0.113.0/24", 64500, RouteState::NotFound),
+		];
+		for (route, origin, state) in cases {
+			assert_eq!(
+				table.validate(&net(route), asn(origin)).state,
+				state,
+				"{route} AS{origin}"
+			);
+		}
+
+		let outcome = table.validate(&net("10.1.2.0/24"), asn(64999));
+		assert!(outcome.state.is_invalid());
+		let covering: Vec<IpNet> = outcome.covering.iter().map(|roa| roa.prefix).collect();
+		assert_eq!(covering, [net("10.1.0.0/16"), net("10.0.0.0/8")]);
+	}
+
+	#[test]
+	fn test_table_extend_merges() {
+		let mut table = RoaTable::new();
+		table.extend([Roa::new(net("10.0.0.0/8"), 8, asn(1)).unwrap()]);
+		table.extend([Roa::new(net("10.0.0.0/8"), 8, asn(2)).unwrap()]);
+		assert_eq!(table.len(), 2);
+		assert_eq!(
+			table.validate(&net("10.0.0.0/8"), asn(1)).state,
+			RouteState::Valid
+		);
+		assert_eq!(table.validate(&net("10.0.0.0/8"), asn(2)).covering.len(), 2);
+	}
+
+	#[test]
+	fn test_roa_serde_roundtrip() {
+		let roa = Roa::new(net("1.0.0.0/24"), 24, asn(13335)).unwrap();
+		let json = serde_json::to_string(&roa).unwrap();
+		assert_eq!(
+			json,
+			r#"{"asn":"AS13335","prefix":"1.0.0.0/24","maxLength":24}"#
+		);
+		assert_eq!(serde_json::from_str::<Roa>(&json).unwrap(), roa);
+		for bad in [
+			r#"{"asn":1,"prefix":"10.0.0.0/8","maxLength":7}"#,
+			r#"{"asn":1,"prefix":"10.0.0.0/8","maxLength":33}"#,
+		] {
+			assert!(serde_json::from_str::<Roa>(bad).is_err(), "{bad}");
+		}
+		assert_eq!(RouteState::InvalidLength.to_string(), "invalid-length");
+	}
+}
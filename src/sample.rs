@@ -0,0 +1,146 @@
+/* src/sample.rs */
+
+use crate::error::NetSemError;
+use crate::iid::with_iid;
+use crate::ip::{IpClass, bits_to_ip, class_prefixes, classify_ip, ip_to_bits};
+use crate::net::IpNet;
+use crate::port::{PortClass, port_class_range};
+use core::net::{IpAddr, Ipv6Addr};
+use rand::Rng;
+use rand::seq::IteratorRandom;
+
+/// Picks a uniformly random address inside `net`.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::IpNet;
+/// use netsem::sample::ip_in_net;
+///
+/// let net: IpNet = "192.168.0.0/16".parse().unwrap();
+/// let ip = ip_in_net(&mut rand::rng(), net);
+/// assert!(net.contains(ip));
+/// ```
+pub fn ip_in_net<R: Rng + ?Sized>(rng: &mut R, net: IpNet) -> IpAddr {
+	let host = ip_to_bits(net.hostmask());
+	bits_to_ip(
+		ip_to_bits(net.network()) | (rng.random::<u128>() & host),
+		net.is_ipv4(),
+	)
+}
+
+/// Picks a random address that [`classify_ip`] maps to `class`.
+///
+/// Non-global classes pick one of the class's prefixes with equal weight,
+/// then an address inside it. `IpClass::Global` draws IPv4 and IPv6 with
+/// equal probability and retries until the result is global.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::sample::ip_of_class;
+/// use netsem::{IpClass, classify_ip};
+///
+/// let ip = ip_of_class(&mut rand::rng(), IpClass::Documentation);
+/// assert_eq!(classify_ip(ip), IpClass::Documentation);
+/// ```
+pub fn ip_of_class<R: Rng + ?Sized>(rng: &mut R, class: IpClass) -> IpAddr {
+	if let Some(net) = class_prefixes(class).choose(rng) {
+		return ip_in_net(rng, net);
+	}
+	loop {
+		let ip = bits_to_ip(rng.random(), rng.random());
+		if classify_ip(ip) == class {
+			return ip;
+		}
+	}
+}
+
+/// Picks a ULA `/48` with a random 40-bit Global ID (RFC 4193 section 3.2).
+///
+/// # Examples
+///
+/// ```
+/// use netsem::sample::ula;
+/// use netsem::{IpClass, classify_ip};
+///
+/// let net = ula(&mut rand::rng());
+/// assert_eq!(net.prefix_len(), 48);
+/// assert_eq!(classify_ip(net.network()), IpClass::Private);
+/// ```
+pub fn ula<R: Rng + ?Sized>(rng: &mut R) -> IpNet {
+	crate::ula::from_global_id(rng.random())
+}
+
+/// Picks a random temporary address in `prefix` (RFC 8981 section 3.3.1).
+///
+/// The interface identifier is drawn at random, redrawing reserved ones.
+///
+/// # Returns
+///
+/// * `Err(NetSemError::InvalidPrefix)` if `prefix` is not an IPv6 `/64`.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::sample::temporary_address;
+///
+/// let prefix = "2001:db8:1:2::/64".parse().unwrap();
+/// let ip = temporary_address(&mut rand::rng(), prefix).unwrap();
+/// assert!(prefix.contains(ip.into()));
+/// ```
+pub fn temporary_address<R: Rng + ?Sized>(
+	rng: &mut R,
+	prefix: IpNet,
+) -> Result<Ipv6Addr, NetSemError> {
+	loop {
+		match with_iid(prefix, rng.random()) {
+			Err(NetSemError::ReservedIid(_)) => {}
+			result => return result,
+		}
+	}
+}
+
+/// Picks a uniformly random port that [`classify_port`](crate::classify_port) maps to `class`.
+pub fn port_of_class<R: Rng + ?Sized>(rng: &mut R, class: PortClass) -> u16 {
+	rng.random_range(port_class_range(class))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::port::classify_port;
+	use rand::SeedableRng;
+	use rand::rngs::StdRng;
+
+	#[test]
+	fn test_ip_of_class() {
+		let mut rng = StdRng::seed_from_u64(7);
+		for &class in IpClass::ALL {
+			for _ in 0..64 {
+				assert_eq!(classify_ip(ip_of_class(&mut rng, class)), class);
+			}
+		}
+	}
+
+	#[test]
+	fn test_ip_in_net() {
+		let mut rng = StdRng::seed_from_u64(7);
+		for s in ["10.0.0.0/8", "2001:db8::/64", "1.2.3.4/32", "::/0"] {
+			let net: IpNet = s.parse().unwrap();
+			for _ in 0..64 {
+				assert!(net.contains(ip_in_net(&mut rng, net)));
+			}
+		}
+	}
+
+	#[test]
+	fn test_port_of_class() {
+		let mut rng = StdRng::seed_from_u64(7);
+		for &class in PortClass::ALL {
+			for _ in 0..64 {
+				assert_eq!(classify_port(port_of_class(&mut rng, class)), class);
+			}
+		}
+	}
+}
@@ -0,0 +1,131 @@
+/* src/serde_str.rs */
+
+//! Use these with `#[serde(with = "...")]` on fields whose types have no
+//! string form of their own.
+//!
+//! # Examples
+//!
+//! ```
+//! use netsem::{IpClass, IpNet};
+//! use serde::Deserialize;
+//! use std::ops::RangeInclusive;
+//!
+//! #[derive(Deserialize)]
+//! struct Policy {
+//!     deny: Vec<IpClass>,
+//!     #[serde(with = "netsem::serde_str::host_or_prefix")]
+//!     upstream: IpNet,
+//!     #[serde(with = "netsem::serde_str::port_range")]
+//!     ports: RangeInclusive<u16>,
+//! }
+//!
+//! let policy: Policy = serde_json::from_str(
+//!     r#"{"deny": ["private", "loopback"], "upstream": "192.0.2.1", "ports": "8000-8100"}"#,
+//! )
+//! .unwrap();
+//! assert_eq!(policy.deny, [IpClass::Private, IpClass::Loopback]);
+//! assert_eq!(policy.upstream.to_string(), "192.0.2.1/32");
+//! assert_eq!(policy.ports, 8000..=8100);
+//! ```
+
+/// Serializes a `RangeInclusive<u16>` in the string form of [`PortRange`](crate::PortRange).
+///
+/// That is `"start-end"` (or `"port"` when both ends match). Deserialization
+/// accepts everything `PortRange` does, including `"start:end"` and a bare
+/// integer port. Prefer a `PortRange` field in new code.
+pub mod port_range {
+	use crate::port::PortRange;
+	use core::ops::RangeInclusive;
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	/// Serializes the range as a string, rejecting empty ranges.
+	pub fn serialize<S: Serializer>(
+		range: &RangeInclusive<u16>,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		PortRange::try_from(range.clone())
+			.map_err(serde::ser::Error::custom)?
+			.serialize(serializer)
+	}
+
+	/// Deserializes the range from a string or an integer.
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<RangeInclusive<u16>, D::Error> {
+		PortRange::deserialize(deserializer).map(Into::into)
+	}
+}
+
+/// Deserializes an [`IpNet`](crate::IpNet) from CIDR notation or a bare address.
+///
+/// A bare address becomes a host prefix (`/32` or `/128`). Serialization
+/// always writes CIDR notation.
+pub mod host_or_prefix {
+	use crate::net::IpNet;
+	use alloc::borrow::Cow;
+	use core::net::IpAddr;
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	/// Serializes the prefix in CIDR notation.
+	pub fn serialize<S: Serializer>(net: &IpNet, serializer: S) -> Result<S::Ok, S::Error> {
+		net.serialize(serializer)
+	}
+
+	/// Deserializes a prefix, treating a bare address as a host prefix.
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<IpNet, D::Error> {
+		let s = <Cow<'de, str>>::deserialize(deserializer)?;
+		if s.contains('/') {
+			return s.parse().map_err(serde::de::Error::custom);
+		}
+		let ip = s.parse::<IpAddr>().map_err(serde::de::Error::custom)?;
+		let len = if ip.is_ipv4() { 32 } else { 128 };
+		IpNet::new(ip, len).map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::net::IpNet;
+	use serde::{Deserialize, Serialize};
+	use std::ops::RangeInclusive;
+
+	#[derive(Debug, Serialize, Deserialize)]
+	struct Config {
+		#[serde(with = "super::port_range")]
+		ports: RangeInclusive<u16>,
+		#[serde(with = "super::host_or_prefix")]
+		net: IpNet,
+	}
+
+	#[test]
+	fn test_port_range_helper() {
+		let cfg: Config = serde_json::from_str(r#"{"ports": 443, "net": "::1"}"#).unwrap();
+		assert_eq!(cfg.ports, 443..=443);
+		assert_eq!(cfg.net.to_string(), "::1/128");
+		assert_eq!(
+			serde_json::to_string(&cfg).unwrap(),
+			r#"{"ports":"443","net":"::1/128"}"#
+		);
+
+		let cfg: Config = serde_json::from_str(r#"{"ports": "10-20", "net": "10.0.0.0/8"}"#).unwrap();
+		assert_eq!(cfg.ports, 10..=20);
+		assert_eq!(
+			serde_json::to_string(&cfg).unwrap(),
+			r#"{"ports":"10-20","net":"10.0.0.0/8"}"#
+		);
+
+		let cfg: Config = serde_json::from_str(r#"{"ports": "10:20", "net": "::/0"}"#).unwrap();
+		assert_eq!(cfg.ports, 10..=20);
+
+		for bad in [
+			r#""20-10""#,
+			r#""+80""#,
+			r#""80-""#,
+			"70000",
+			r#""1-70000""#,
+		] {
+			let json = format!(r#"{{"ports": {bad}, "net": "::/0"}}"#);
+			assert!(serde_json::from_str::<Config>(&json).is_err(), "{bad}");
+		}
+	}
+}
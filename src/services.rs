@@ -0,0 +1,546 @@
+/* src/services.rs */
+
+use crate::error::NetSemError;
+use crate::port::{TransportPort, parse_port_with};
+use crate::proto::Protocol;
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+const TCP: &[Protocol] = &[Protocol::Tcp];
+const UDP: &[Protocol] = &[Protocol::Udp];
+const TCP_UDP: &[Protocol] = &[Protocol::Tcp, Protocol::Udp];
+const TCP_SCTP: &[Protocol] = &[Protocol::Tcp, Protocol::Sctp];
+const TCP_UDP_SCTP: &[Protocol] = &[Protocol::Tcp, Protocol::Udp, Protocol::Sctp];
+
+/// Snapshot of commonly used entries of the IANA "Service Name and Transport
+/// Protocol Port Number Registry", in port order.
+const SNAPSHOT: &[(&str, u16, &[Protocol])] = &[
+	("echo", 7, TCP_UDP),
+	("discard", 9, TCP_UDP_SCTP),
+	("daytime", 13, TCP_UDP),
+	("qotd", 17, TCP_UDP),
+	("chargen", 19, TCP_UDP),
+	("ftp-data", 20, TCP_UDP_SCTP),
+	("ftp", 21, TCP_UDP_SCTP),
+	("ssh", 22, TCP_UDP_SCTP),
+	("telnet", 23, TCP_UDP),
+	("smtp", 25, TCP_UDP),
+	("time", 37, TCP_UDP),
+	("nicname", 43, TCP_UDP),
+	("tacacs", 49, TCP_UDP),
+	("domain", 53, TCP_UDP),
+	("bootps", 67, TCP_UDP),
+	("bootpc", 68, TCP_UDP),
+	("tftp", 69, TCP_UDP),
+	("gopher", 70, TCP_UDP),
+	("finger", 79, TCP_UDP),
+	("http", 80, TCP_UDP_SCTP),
+	("kerberos", 88, TCP_UDP),
+	("pop3", 110, TCP_UDP),
+	("sunrpc", 111, TCP_UDP),
+	("auth", 113, TCP_UDP),
+	("nntp", 119, TCP_UDP),
+	("ntp", 123, TCP_UDP),
+	("epmap", 135, TCP_UDP),
+	("netbios-ns", 137, TCP_UDP),
+	("netbios-dgm", 138, TCP_UDP),
+	("netbios-ssn", 139, TCP_UDP),
+	("imap", 143, TCP_UDP),
+	("snmp", 161, TCP_UDP),
+	("snmptrap", 162, TCP_UDP),
+	("bgp", 179, TCP_UDP_SCTP),
+	("irc", 194, TCP_UDP),
+	("ldap", 389, TCP_UDP),
+	("https", 443, TCP_UDP_SCTP),
+	("microsoft-ds", 445, TCP_UDP),
+	("kpasswd", 464, TCP_UDP),
+	("isakmp", 500, TCP_UDP),
+	("exec", 512, TCP),
+	("login", 513, TCP),
+	("shell", 514, TCP),
+	("syslog", 514, UDP),
+	("printer", 515, TCP_UDP),
+	("ripng", 521, TCP_UDP),
+	("dhcpv6-client", 546, TCP_UDP),
+	("dhcpv6-server", 547, TCP_UDP),
+	("rtsp", 554, TCP_UDP),
+	("nntps", 563, TCP_UDP),
+	("submission", 587, TCP_UDP),
+	("ipp", 631, TCP_UDP),
+	("ldaps", 636, TCP_UDP),
+	("kerberos-adm", 749, TCP_UDP),
+	("domain-s", 853, TCP_UDP),
+	("rsync", 873, TCP_UDP),
+	("ftps-data", 989, TCP_UDP),
+	("ftps", 990, TCP_UDP),
+	("telnets", 992, TCP_UDP),
+	("imaps", 993, TCP_UDP),
+	("pop3s", 995, TCP_UDP),
+	("socks", 1080, TCP_UDP),
+	("openvpn", 1194, TCP_UDP),
+	("ms-sql-s", 1433, TCP_UDP),
+	("ms-sql-m", 1434, TCP_UDP),
+	("l2tp", 1701, TCP_UDP),
+	("pptp", 1723, TCP_UDP),
+	("radius", 1812, TCP_UDP),
+	("radius-acct", 1813, TCP_UDP),
+	("mqtt", 1883, TCP_UDP),
+	("nfs", 2049, TCP_UDP_SCTP),
+	("etcd-client", 2379, TCP),
+	("etcd-server", 2380, TCP),
+	("iscsi-target", 3260, TCP_UDP),
+	("mysql", 3306, TCP_UDP),
+	("ms-wbt-server", 3389, TCP_UDP),
+	("stun", 3478, TCP_UDP),
+	("turn", 3478, TCP_UDP),
+	("svn", 3690, TCP_UDP),
+	("bfd-control", 3784, UDP),
+	("diameter", 3868, TCP_SCTP),
+	("ipsec-nat-t", 4500, TCP_UDP),
+	("sip", 5060, TCP_UDP_SCTP),
+	("sips", 5061, TCP_UDP_SCTP),
+	("xmpp-client", 5222, TCP),
+	("xmpp-server", 5269, TCP),
+	("stuns", 5349, TCP_UDP),
+	("turns", 5349, TCP_UDP),
+	("mdns", 5353, TCP_UDP),
+	("llmnr", 5355, TCP_UDP),
+	("postgresql", 5432, TCP_UDP),
+	("amqp", 5672, TCP_UDP_SCTP),
+	("coap", 5683, TCP_UDP),
+	("rfb", 5900, TCP_UDP),
+	("x11", 6000, TCP_UDP),
+	("http-alt", 8080, TCP_UDP),
+	("puppet", 8140, TCP),
+	("secure-mqtt", 8883, TCP_UDP),
+	("git", 9418, TCP_UDP),
+	("zabbix-agent", 10050, TCP_UDP),
+	("zabbix-trapper", 10051, TCP_UDP),
+	("memcache", 11211, TCP_UDP),
+	("mongodb", 27017, TCP_UDP),
+];
+
+/// Returns the IANA service name registered for a port, from the built-in snapshot.
+///
+/// When several names share a port (e.g. `stun` and `turn`), the first
+/// registered one is returned; use [`ServiceRegistry::names`] for all of them.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::TransportPort;
+/// use netsem::services::service_name;
+///
+/// assert_eq!(service_name(TransportPort::tcp(5432)), Some("postgresql"));
+/// assert_eq!(service_name(TransportPort::udp(514)), Some("syslog"));
+/// assert_eq!(service_name(TransportPort::tcp(514)), Some("shell"));
+/// assert_eq!(service_name(TransportPort::tcp(1)), None);
+/// ```
+#[must_use]
+pub fn service_name(port: TransportPort) -> Option<&'static str> {
+	SNAPSHOT
+		.iter()
+		.find(|&&(_, p, protos)| p == port.port() && protos.contains(&port.proto()))
+		.map(|&(name, ..)| name)
+}
+
+/// Returns every port registered for a service name in the built-in snapshot.
+///
+/// Names are matched case-insensitively.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::TransportPort;
+/// use netsem::services::service_ports;
+///
+/// let ports: Vec<_> = service_ports("domain").collect();
+/// assert_eq!(ports, [TransportPort::tcp(53), TransportPort::udp(53)]);
+/// ```
+pub fn service_ports(name: &str) -> impl Iterator<Item = TransportPort> + '_ {
+	SNAPSHOT
+		.iter()
+		.filter(move |(n, ..)| n.eq_ignore_ascii_case(name))
+		.flat_map(|&(_, port, protos)| {
+			protos
+				.iter()
+				.map(move |&proto| TransportPort::new_unchecked(proto, port))
+		})
+}
+
+/// One line of an `/etc/services`-format file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceEntry {
+	/// The official service name.
+	pub name: String,
+	/// The port and transport protocol.
+	pub port: TransportPort,
+	/// Alternative names, in file order.
+	pub aliases: Vec<String>,
+}
+
+impl ServiceEntry {
+	/// Returns the official name followed by the aliases.
+	pub fn names(&self) -> impl Iterator<Item = &str> {
+		core::iter::once(self.name.as_str()).chain(self.aliases.iter().map(String::as_str))
+	}
+}
+
+/// Parses an `/etc/services`-format file.
+///
+/// Each line is `name port/protocol [alias ...]`, optionally followed by a
+/// `#` comment. Blank and comment-only lines are skipped, as are entries
+/// for protocols without ports (e.g. the historical `ddp`), which resolvers
+/// ignore too.
+///
+/// # Returns
+///
+/// * `Err(NetSemError::InvalidServices)` with the 1-based line number of the
+///   first malformed line.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::TransportPort;
+/// use netsem::services::parse_services;
+///
+/// let entries = parse_services(
+///     "# local additions\n\
+///      http  80/tcp  www www-http  # World Wide Web\n\
+///      myapp 9000/udp\n",
+/// )
+/// .unwrap();
+/// assert_eq!(entries[0].port, TransportPort::tcp(80));
+/// assert_eq!(entries[0].aliases, ["www", "www-http"]);
+/// assert_eq!(entries[1].name, "myapp");
+/// ```
+pub fn parse_services(input: &str) -> Result<Vec<ServiceEntry>, NetSemError> {
+	let mut entries = Vec::new();
+	for (index, line) in input.lines().enumerate() {
+		let line = line.split_once('#').map_or(line, |(data, _)| data);
+		let mut fields = line.split_whitespace();
+		let Some(name) = fields.next() else {
+			continue;
+		};
+		let err = |reason: String| NetSemError::InvalidServices {
+			line: index + 1,
+			reason,
+		};
+		let spec = fields
+			.next()
+			.ok_or_else(|| err(alloc::format!("missing port/protocol for {name}")))?;
+		let (port, proto) = spec
+			.split_once('/')
+			.ok_or_else(|| err(alloc::format!("expected port/protocol, found {spec:?}")))?;
+		if port.is_empty() || !port.bytes().all(|b| b.is_ascii_digit()) {
+			return Err(err(alloc::format!("invalid port {port:?}")));
+		}
+		let port = port
+			.parse::<u16>()
+			.map_err(|_| err(alloc::format!("port {port} out of range")))?;
+		let Some(proto) = proto.parse::<Protocol>().ok().filter(|p| p.has_ports()) else {
+			continue;
+		};
+		entries.push(ServiceEntry {
+			name: name.to_owned(),
+			port: TransportPort::new_unchecked(proto, port),
+			aliases: fields.map(str::to_owned).collect(),
+		});
+	}
+	Ok(entries)
+}
+
+/// Reads and parses an `/etc/services`-format file from disk.
+///
+/// See [`parse_services`] for the format.
+#[cfg(feature = "std")]
+pub fn load_services(path: impl AsRef<std::path::Path>) -> Result<Vec<ServiceEntry>, NetSemError> {
+	let path = path.as_ref();
+	let input = std::fs::read_to_string(path).map_err(|e| NetSemError::ReadFailed {
+		path: path.display().to_string(),
+		source: e,
+	})?;
+	parse_services(&input)
+}
+
+/// A two-way table of service names and transport ports.
+///
+/// Start from [`ServiceRegistry::builtin`] and [`overlay`](Self::overlay)
+/// local files on top: a layer replaces the names of every port it lists and,
+/// per protocol, the ports of every name it lists. Names are matched
+/// case-insensitively and keep their original spelling.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::TransportPort;
+/// use netsem::services::{ServiceRegistry, parse_services};
+///
+/// let mut registry = ServiceRegistry::builtin();
+/// assert_eq!(registry.name(TransportPort::tcp(8080)), Some("http-alt"));
+///
+/// registry.overlay(parse_services("webapp 8080/tcp api\npostgresql 6432/tcp\n").unwrap());
+/// assert_eq!(registry.names(TransportPort::tcp(8080)), ["webapp", "api"]);
+/// assert_eq!(registry.port("PostgreSQL", netsem::Protocol::Tcp), Some(6432));
+/// assert_eq!(registry.port("postgresql", netsem::Protocol::Udp), Some(5432));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ServiceRegistry {
+	by_port: BTreeMap<TransportPort, Vec<String>>,
+	by_name: BTreeMap<String, Vec<TransportPort>>,
+}
+
+impl ServiceRegistry {
+	/// Creates an empty registry.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a registry holding the built-in IANA snapshot.
+	#[must_use]
+	pub fn builtin() -> Self {
+		SNAPSHOT
+			.iter()
+			.flat_map(|&(name, port, protos)| {
+				protos.iter().map(move |&proto| ServiceEntry {
+					name: name.to_owned(),
+					port: TransportPort::new_unchecked(proto, port),
+					aliases: Vec::new(),
+				})
+			})
+			.collect()
+	}
+
+	/// Adds an entry alongside any existing names and ports.
+	pub fn insert(&mut self, entry: ServiceEntry) {
+		let ServiceEntry {
+			name,
+			port,
+			aliases,
+		} = entry;
+		for name in core::iter::once(name).chain(aliases) {
+			let ports = self.by_name.entry(name.to_ascii_lowercase()).or_default();
+			if !ports.contains(&port) {
+				ports.push(port);
+			}
+			let names = self.by_port.entry(port).or_default();
+			if !names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+				names.push(name);
+			}
+		}
+	}
+
+	/// Adds a layer of entries that overrides what the registry already holds.
+	///
+	/// Every port listed in `entries` loses its previous names, and every
+	/// name loses its previous ports for the same protocol, before the layer is
+	/// inserted. Entries within one layer do not override each other.
+	pub fn overlay(&mut self, entries: impl IntoIterator<Item = ServiceEntry>) {
+		let entries: Vec<ServiceEntry> = entries.into_iter().collect();
+		for entry in &entries {
+			self.remove_port(entry.port);
+			for name in entry.names() {
+				self.remove_name(name, entry.port.proto());
+			}
+		}
+		for entry in entries {
+			self.insert(entry);
+		}
+	}
+
+	/// Returns every name for a port, official name first.
+	#[must_use]
+	pub fn names(&self, port: TransportPort) -> &[String] {
+		self.by_port.get(&port).map_or(&[], Vec::as_slice)
+	}
+
+	/// Returns the first name for a port.
+	#[must_use]
+	pub fn name(&self, port: TransportPort) -> Option<&str> {
+		self.names(port).first().map(String::as_str)
+	}
+
+	/// Returns every port a name (or alias) is registered for.
+	#[must_use]
+	pub fn ports(&self, name: &str) -> &[TransportPort] {
+		self
+			.by_name
+			.get(&name.to_ascii_lowercase())
+			.map_or(&[], Vec::as_slice)
+	}
+
+	/// Returns the first port a name is registered for with the given protocol.
+	#[must_use]
+	pub fn port(&self, name: &str, proto: Protocol) -> Option<u16> {
+		self
+			.ports(name)
+			.iter()
+			.find(|p| p.proto() == proto)
+			.map(|p| p.port())
+	}
+
+	/// Parses a port or service name like [`parse_port`](crate::parse_port), resolving names in this registry.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use netsem::services::{ServiceRegistry, parse_services};
+	///
+	/// let mut registry = ServiceRegistry::builtin();
+	/// registry.overlay(parse_services("webapp 8443/tcp\n").unwrap());
+	/// assert_eq!(registry.parse_port("webapp").unwrap(), 8443);
+	/// assert_eq!(registry.parse_port("https").unwrap(), 443);
+	/// ```
+	pub fn parse_port(&self, s: &str) -> Result<u16, NetSemError> {
+		parse_port_with(s, None, |name| self.ports(name).to_vec()).map(|p| p.0)
+	}
+
+	/// Parses a port like [`parse_port_spec`](crate::parse_port_spec), resolving names in this registry.
+	pub fn parse_port_spec(
+		&self,
+		s: &str,
+		default_proto: Protocol,
+	) -> Result<TransportPort, NetSemError> {
+		parse_port_with(s, Some(default_proto), |name| self.ports(name).to_vec())
+			.and_then(|(port, proto)| TransportPort::new(proto.unwrap_or(default_proto), port))
+	}
+
+	fn remove_port(&mut self, port: TransportPort) {
+		for name in self.by_port.remove(&port).unwrap_or_default() {
+			let key = name.to_ascii_lowercase();
+			if let Some(ports) = self.by_name.get_mut(&key) {
+				ports.retain(|&p| p != port);
+				if ports.is_empty() {
+					self.by_name.remove(&key);
+				}
+			}
+		}
+	}
+
+	fn remove_name(&mut self, name: &str, proto: Protocol) {
+		let key = name.to_ascii_lowercase();
+		let Some(ports) = self.by_name.get_mut(&key) else {
+			return;
+		};
+		let (removed, kept): (Vec<_>, Vec<_>) = ports.iter().partition(|p| p.proto() == proto);
+		*ports = kept;
+		if ports.is_empty() {
+			self.by_name.remove(&key);
+		}
+		for port in removed {
+			if let Some(names) = self.by_port.get_mut(&port) {
+				names.retain(|n| !n.eq_ignore_ascii_case(name));
+				if names.is_empty() {
+					self.by_port.remove(&port);
+				}
+			}
+		}
+	}
+}
+
+impl FromIterator<ServiceEntry> for ServiceRegistry {
+	fn from_iter<I: IntoIterator<Item = ServiceEntry>>(iter: I) -> Self {
+		let mut registry = Self::new();
+		for entry in iter {
+			registry.insert(entry);
+		}
+		registry
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_snapshot_is_sorted_and_ported() {
+		assert!(SNAPSHOT.windows(2).all(|w| w[0].1 <= w[1].1));
+		assert!(
+			SNAPSHOT
+				.iter()
+				.all(|(_, _, protos)| protos.iter().all(|p| p.has_ports()))
+		);
+	}
+
+	#[test]
+	fn test_builtin_lookups() {
+		let registry = ServiceRegistry::builtin();
+		assert_eq!(registry.name(TransportPort::tcp(5432)), Some("postgresql"));
+		assert_eq!(registry.names(TransportPort::udp(3478)), ["stun", "turn"]);
+		assert_eq!(
+			registry.ports("HTTPS"),
+			[
+				TransportPort::tcp(443),
+				TransportPort::udp(443),
+				TransportPort::new(Protocol::Sctp, 443).unwrap()
+			]
+		);
+		assert_eq!(registry.port("syslog", Protocol::Udp), Some(514));
+		assert_eq!(registry.port("syslog", Protocol::Tcp), None);
+		assert!(registry.names(TransportPort::tcp(1)).is_empty());
+		assert_eq!(service_ports("nope").count(), 0);
+	}
+
+	#[test]
+	fn test_parse_services() {
+		let entries = parse_services(
+			"\
+# comment only
+http\t80/tcp\twww # trailing
+rtmp 1/ddp
+  blank-indented 7000/udp
+
+ssh 22/sctp
+",
+		)
+		.unwrap();
+		assert_eq!(entries.len(), 3);
+		assert_eq!(entries[0].names().collect::<Vec<_>>(), ["http", "www"]);
+		assert_eq!(entries[1].port, TransportPort::udp(7000));
+		assert_eq!(entries[2].port.proto(), Protocol::Sctp);
+
+		for (input, line) in [
+			("http\n", 1),
+			("ok 1/tcp\nhttp 80\n", 2),
+			("http x/tcp\n", 1),
+			("http 65536/tcp\n", 1),
+			("http -1/tcp\n", 1),
+		] {
+			match parse_services(input) {
+				Err(NetSemError::InvalidServices { line: l, .. }) => assert_eq!(l, line, "{input}"),
+				other => panic!("{input:?}: {other:?}"),
+			}
+		}
+	}
+
+	#[test]
+	fn test_overlay_overrides_builtin() {
+		let mut registry = ServiceRegistry::builtin();
+		registry
+			.overlay(parse_services("grafana 3000/tcp\nhttp 8000/tcp web\nhttp 8001/tcp\n").unwrap());
+		assert_eq!(registry.name(TransportPort::tcp(3000)), Some("grafana"));
+		// http moved for TCP only; the port it left has no names.
+		assert_eq!(registry.port("http", Protocol::Tcp), Some(8000));
+		assert_eq!(registry.port("http", Protocol::Udp), Some(80));
+		assert!(registry.names(TransportPort::tcp(80)).is_empty());
+		// Entries in one layer accumulate.
+		assert_eq!(
+			registry.ports("http")[1..],
+			[
+				TransportPort::new(Protocol::Sctp, 80).unwrap(),
+				TransportPort::tcp(8000),
+				TransportPort::tcp(8001),
+			]
+		);
+		assert_eq!(registry.ports("WEB"), [TransportPort::tcp(8000)]);
+
+		// An overriding port drops names that shared it.
+		registry.overlay(parse_services("turn 3478/udp\n").unwrap());
+		assert_eq!(registry.names(TransportPort::udp(3478)), ["turn"]);
+		assert_eq!(registry.port("stun", Protocol::Udp), None);
+		assert_eq!(registry.port("stun", Protocol::Tcp), Some(3478));
+	}
+}
@@ -1,7 +1,12 @@
 /* src/socket.rs */
 
 use crate::error::NetSemError;
-use std::net::SocketAddr;
+use crate::port::TransportPort;
+use crate::proto::Protocol;
+use alloc::borrow::ToOwned;
+use core::fmt;
+use core::net::SocketAddr;
+use core::str::FromStr;
 
 /// Validates a string as a socket address (IP:Port).
 ///
@@ -25,11 +30,108 @@ use std::net::SocketAddr;
 /// assert!(validate_socket_addr("not-an-address").is_err());
 /// ```
 pub fn validate_socket_addr(s: &str) -> Result<SocketAddr, NetSemError> {
-	// std::net::SocketAddr parsing handles "[ipv6]:port" and "ipv4:port"
+	// core::net::SocketAddr parsing handles "[ipv6]:port" and "ipv4:port"
 	s.parse::<SocketAddr>()
 		.map_err(|_| NetSemError::InvalidSocketAddr(s.to_owned()))
 }
 
+/// A socket address qualified by its transport protocol, as in `"[::1]:53/udp"`.
+///
+/// The string form (used by `Display`, `FromStr` and serde) appends
+/// `/protocol` to the socket address, as [`TransportPort`] does to a port.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{Protocol, TransportAddr, TransportPort};
+///
+/// let addr: TransportAddr = "127.0.0.1:53/udp".parse().unwrap();
+/// assert_eq!(addr.proto(), Protocol::Udp);
+/// assert_eq!(addr.transport_port(), TransportPort::udp(53));
+/// assert!("127.0.0.1:53".parse::<TransportAddr>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TransportAddr {
+	addr: SocketAddr,
+	proto: Protocol,
+}
+
+impl TransportAddr {
+	/// Creates a transport address, rejecting protocols without ports.
+	pub fn new(addr: SocketAddr, proto: Protocol) -> Result<Self, NetSemError> {
+		let port = TransportPort::new(proto, addr.port())?;
+		Ok(Self {
+			addr,
+			proto: port.proto(),
+		})
+	}
+
+	/// Returns the socket address.
+	#[must_use]
+	pub const fn addr(&self) -> SocketAddr {
+		self.addr
+	}
+
+	/// Returns the transport protocol.
+	#[must_use]
+	pub const fn proto(&self) -> Protocol {
+		self.proto
+	}
+
+	/// Returns the transport-qualified port.
+	#[must_use]
+	pub fn transport_port(&self) -> TransportPort {
+		TransportPort::new_unchecked(self.proto, self.addr.port())
+	}
+}
+
+impl fmt::Display for TransportAddr {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{}", self.addr, self.proto)
+	}
+}
+
+impl FromStr for TransportAddr {
+	type Err = NetSemError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (addr, proto) = s
+			.rsplit_once('/')
+			.ok_or_else(|| NetSemError::InvalidSocketAddr(s.to_owned()))?;
+		Self::new(validate_socket_addr(addr)?, proto.parse()?)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for TransportAddr {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TransportAddr {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+		s.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for TransportAddr {
+	fn schema_name() -> alloc::borrow::Cow<'static, str> {
+		"TransportAddr".into()
+	}
+
+	fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		schemars::json_schema!({
+			"type": "string",
+			"description": "Transport-qualified socket address such as \"127.0.0.1:53/udp\" or \"[::1]:443/tcp\".",
+			"examples": ["127.0.0.1:53/udp", "[::1]:443/tcp"]
+		})
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -47,4 +149,23 @@ mod tests {
 			NetSemError::InvalidSocketAddr(_)
 		));
 	}
+
+	#[test]
+	fn test_transport_addr() {
+		let addr: TransportAddr = "[::1]:443/tcp".parse().unwrap();
+		assert_eq!(addr.addr(), "[::1]:443".parse().unwrap());
+		assert_eq!(addr.to_string(), "[::1]:443/tcp");
+		assert_eq!(
+			"10.0.0.1:5060/17".parse::<TransportAddr>().unwrap().proto(),
+			Protocol::Udp
+		);
+		assert!(matches!(
+			"10.0.0.1/udp".parse::<TransportAddr>().unwrap_err(),
+			NetSemError::InvalidSocketAddr(_)
+		));
+		assert!(matches!(
+			"10.0.0.1:0/gre".parse::<TransportAddr>().unwrap_err(),
+			NetSemError::InvalidTransportPort(_)
+		));
+	}
 }
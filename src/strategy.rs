@@ -0,0 +1,163 @@
+/* src/strategy.rs */
+
+use crate::ip::{IpClass, bits_to_ip, class_prefixes, classify_ip, ip_to_bits};
+use crate::net::IpNet;
+use crate::port::{PortClass, port_class_range};
+use proptest::prelude::*;
+use proptest::strategy::Union;
+use std::net::{IpAddr, SocketAddr};
+
+/// Generates addresses inside `net`, shrinking towards its network address.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::IpNet;
+/// use netsem::strategy::ip_in_net;
+/// use proptest::prelude::*;
+///
+/// let net: IpNet = "10.0.0.0/8".parse().unwrap();
+/// proptest!(|(ip in ip_in_net(net))| {
+///     prop_assert!(net.contains(ip));
+/// });
+/// ```
+pub fn ip_in_net(net: IpNet) -> impl Strategy<Value = IpAddr> + Clone {
+	let base = ip_to_bits(net.network());
+	let host = ip_to_bits(net.hostmask());
+	let ipv4 = net.is_ipv4();
+	any::<u128>().prop_map(move |r| bits_to_ip(base | (r & host), ipv4))
+}
+
+/// Generates addresses that [`classify_ip`] maps to `class`.
+///
+/// Classes with [`class_prefixes`] pick one of them with equal weight;
+/// `IpClass::Global`, and any class without prefixes, draws from the whole
+/// address space and rejects addresses of other classes.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::strategy::ip_of_class;
+/// use netsem::{IpClass, classify_ip};
+/// use proptest::prelude::*;
+///
+/// proptest!(|(ip in ip_of_class(IpClass::Private))| {
+///     prop_assert_eq!(classify_ip(ip), IpClass::Private);
+/// });
+/// ```
+pub fn ip_of_class(class: IpClass) -> BoxedStrategy<IpAddr> {
+	let nets: Vec<_> = class_prefixes(class)
+		.map(|net| ip_in_net(net).boxed())
+		.collect();
+	if nets.is_empty() {
+		return any::<IpAddr>()
+			.prop_filter("address of the class", move |ip| classify_ip(*ip) == class)
+			.boxed();
+	}
+	Union::new(nets).boxed()
+}
+
+/// Generates ports that [`classify_port`](crate::classify_port) maps to `class`.
+pub fn port_of_class(class: PortClass) -> impl Strategy<Value = u16> + Clone {
+	port_class_range(class)
+}
+
+/// Generates arbitrary IPv4 and IPv6 socket addresses, including port 0.
+pub fn socket_addr() -> impl Strategy<Value = SocketAddr> + Clone {
+	(any::<IpAddr>(), any::<u16>()).prop_map(SocketAddr::from)
+}
+
+/// Generates socket addresses whose IP and port fall in the given classes.
+pub fn socket_addr_of_class(ip: IpClass, port: PortClass) -> impl Strategy<Value = SocketAddr> {
+	(ip_of_class(ip), port_of_class(port)).prop_map(SocketAddr::from)
+}
+
+impl Arbitrary for IpClass {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+		proptest::sample::select(Self::ALL).boxed()
+	}
+}
+
+impl Arbitrary for PortClass {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+		proptest::sample::select(Self::ALL).boxed()
+	}
+}
+
+impl Arbitrary for IpNet {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+		any::<IpAddr>()
+			.prop_flat_map(|addr| {
+				let max: u8 = if addr.is_ipv4() { 32 } else { 128 };
+				(0..=max).prop_map(move |len| {
+					Self::new(addr, len).expect("prefix length is within the address width")
+				})
+			})
+			.boxed()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::port::classify_port;
+	use proptest::test_runner::TestRunner;
+
+	#[test]
+	fn test_ipv4_net_lengths_are_uniform() {
+		let mut runner = TestRunner::deterministic();
+		let strategy = any::<IpNet>();
+		let lens: Vec<u8> = (0..2000)
+			.map(|_| strategy.new_tree(&mut runner).unwrap().current())
+			.filter(IpNet::is_ipv4)
+			.map(|net| net.prefix_len())
+			.collect();
+		let host_routes = lens.iter().filter(|&&len| len == 32).count();
+		assert!(
+			host_routes * 10 < lens.len(),
+			"{host_routes} of {}",
+			lens.len()
+		);
+		assert!(lens.iter().any(|&len| len < 8));
+	}
+
+	#[test]
+	fn test_special_classes_have_prefixes() {
+		// Filtering the whole space only finds Global in practice.
+		for &class in IpClass::ALL {
+			let has_prefixes = class_prefixes(class).next().is_some();
+			assert_eq!(has_prefixes, class != IpClass::Global, "{class}");
+		}
+	}
+
+	proptest! {
+		#[test]
+		fn test_ip_of_class(
+			(class, ip) in any::<IpClass>().prop_flat_map(|c| (Just(c), ip_of_class(c)))
+		) {
+			prop_assert_eq!(classify_ip(ip), class);
+		}
+
+		#[test]
+		fn test_ip_in_net((net, ip) in any::<IpNet>().prop_flat_map(|n| (Just(n), ip_in_net(n)))) {
+			prop_assert!(net.contains(ip));
+		}
+
+		#[test]
+		fn test_socket_addr_of_class(
+			addr in socket_addr_of_class(IpClass::Loopback, PortClass::Dynamic)
+		) {
+			prop_assert_eq!(classify_ip(addr.ip()), IpClass::Loopback);
+			prop_assert_eq!(classify_port(addr.port()), PortClass::Dynamic);
+		}
+	}
+}
@@ -0,0 +1,303 @@
+/* src/summary.rs */
+
+use crate::ip::{IpClass, IpClassSet, bits_to_ip, class_prefixes, ip_to_bits};
+use crate::net::IpNet;
+use crate::range::IpRange;
+use alloc::vec::Vec;
+
+/// Returns the smallest prefix covering every address or prefix in `items`.
+///
+/// Accepts anything convertible into an [`IpNet`], so both `&[IpAddr]` and
+/// `&[IpNet]` work (addresses count as host prefixes).
+///
+/// # Returns
+///
+/// * `Some(IpNet)` with the longest prefix length that still covers all items.
+/// * `None` if `items` is empty or mixes IPv4 and IPv6.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{IpNet, common_supernet};
+/// use std::net::IpAddr;
+///
+/// let ips: Vec<IpAddr> = ["10.0.1.7", "10.0.3.200"].iter().map(|s| s.parse().unwrap()).collect();
+/// assert_eq!(common_supernet(&ips).unwrap().to_string(), "10.0.0.0/22");
+///
+/// let nets: Vec<IpNet> = ["2001:db8:1::/48", "2001:db8:ff::/48"]
+///     .iter()
+///     .map(|s| s.parse().unwrap())
+///     .collect();
+/// assert_eq!(common_supernet(&nets).unwrap().to_string(), "2001:db8::/40");
+/// ```
+#[must_use]
+pub fn common_supernet<T: Copy + Into<IpNet>>(items: &[T]) -> Option<IpNet> {
+	let mut nets = items.iter().map(|&item| item.into());
+	let first = nets.next()?;
+	let mut low = ip_to_bits(first.network());
+	let mut high = ip_to_bits(first.last());
+	for net in nets {
+		if net.is_ipv4() != first.is_ipv4() {
+			return None;
+		}
+		low = low.min(ip_to_bits(net.network()));
+		high = high.max(ip_to_bits(net.last()));
+	}
+	Some(covering(low, high, first.is_ipv4()))
+}
+
+/// Merges prefixes into the smallest equivalent list without adding any address.
+///
+/// Duplicates and nested prefixes are dropped, and adjacent prefixes are
+/// combined. The result is sorted, IPv4 first.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{IpNet, aggregate};
+///
+/// let nets: Vec<IpNet> = ["10.0.0.0/25", "10.0.0.128/25", "10.0.1.0/24", "10.0.0.64/26"]
+///     .iter()
+///     .map(|s| s.parse().unwrap())
+///     .collect();
+/// let merged: Vec<String> = aggregate(&nets).iter().map(ToString::to_string).collect();
+/// assert_eq!(merged, ["10.0.0.0/23"]);
+/// ```
+#[must_use]
+pub fn aggregate(nets: &[IpNet]) -> Vec<IpNet> {
+	let mut spans: Vec<(bool, u128, u128)> = nets
+		.iter()
+		.map(|net| {
+			(
+				net.is_ipv6(),
+				ip_to_bits(net.network()),
+				ip_to_bits(net.last()),
+			)
+		})
+		.collect();
+	spans.sort_unstable();
+
+	let mut merged: Vec<(bool, u128, u128)> = Vec::with_capacity(spans.len());
+	for (ipv6, start, end) in spans {
+		if let Some(last) = merged.last_mut()
+			&& last.0 == ipv6
+			&& last.2.checked_add(1).is_none_or(|next| start <= next)
+		{
+			last.2 = last.2.max(end);
+		} else {
+			merged.push((ipv6, start, end));
+		}
+	}
+
+	merged
+		.into_iter()
+		.flat_map(|(ipv6, start, end)| {
+			IpRange::new(bits_to_ip(start, !ipv6), bits_to_ip(end, !ipv6))
+				.expect("merged spans are ordered")
+				.to_prefixes()
+		})
+		.collect()
+}
+
+/// Result of a lossy [`summarize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+	/// The summary prefixes, sorted, IPv4 first.
+	pub prefixes: Vec<IpNet>,
+	/// Number of addresses covered by the summary but not by the input
+	/// (saturating at `u128::MAX`).
+	pub added_addresses: u128,
+	/// Special-purpose classes (see [`class_prefixes`]) whose ranges overlap the
+	/// added address space, e.g. `Private` when a summary of public routes now
+	/// swallows part of `10.0.0.0/8`.
+	pub added_classes: IpClassSet,
+}
+
+/// Reduces a prefix list to at most `max` entries, accepting extra coverage.
+///
+/// The input is first [`aggregate`]d without loss. While more than `max`
+/// prefixes remain, the pair of neighbouring prefixes whose covering
+/// supernet adds the fewest new addresses is replaced by that supernet.
+/// IPv4 and IPv6 are never merged with each other, so the result keeps at
+/// least one prefix per family present even if `max` is smaller.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::{IpClass, IpNet, summarize};
+///
+/// let nets: Vec<IpNet> = ["9.255.255.0/24", "11.0.0.0/24", "11.0.1.0/24"]
+///     .iter()
+///     .map(|s| s.parse().unwrap())
+///     .collect();
+///
+/// let summary = summarize(&nets, 1);
+/// assert_eq!(summary.prefixes, ["8.0.0.0/6".parse::<IpNet>().unwrap()]);
+/// assert_eq!(summary.added_addresses, (1 << 26) - 3 * 256);
+/// assert!(summary.added_classes.contains(IpClass::Private));
+/// ```
+#[must_use]
+pub fn summarize(nets: &[IpNet], max: usize) -> Summary {
+	let exact = aggregate(nets);
+	let mut prefixes = exact.clone();
+	let mut added_addresses = 0u128;
+
+	while prefixes.len() > max {
+		let best = prefixes
+			.windows(2)
+			.enumerate()
+			.filter(|(_, pair)| pair[0].is_ipv4() == pair[1].is_ipv4())
+			.map(|(i, pair)| {
+				let net = covering(
+					ip_to_bits(pair[0].network()),
+					ip_to_bits(pair[1].last()),
+					pair[0].is_ipv4(),
+				);
+				(merge_cost(&prefixes, i, &net), i, net)
+			})
+			.min_by_key(|&(cost, i, _)| (cost, i));
+		let Some((cost, _, net)) = best else {
+			break;
+		};
+		added_addresses = added_addresses.saturating_add(cost);
+		prefixes.retain(|p| !net.contains_net(p));
+		prefixes.push(net);
+		prefixes = aggregate(&prefixes);
+	}
+
+	let mut added_classes = IpClassSet::new();
+	for &class in IpClass::ALL {
+		let overlaps_added = class_prefixes(class).any(|special| {
+			prefixes.iter().any(|p| {
+				let overlap = if p.contains_net(&special) {
+					Some(special)
+				} else if special.contains_net(p) {
+					Some(*p)
+				} else {
+					None
+				};
+				// `exact` is aggregated, so an overlap is fully covered by the
+				// input only if a single input prefix contains it.
+				overlap.is_some_and(|o| !exact.iter().any(|e| e.contains_net(&o)))
+			})
+		});
+		if overlaps_added {
+			added_classes.insert(class);
+		}
+	}
+
+	Summary {
+		prefixes,
+		added_addresses,
+		added_classes,
+	}
+}
+
+/// Smallest prefix covering the addresses `low..=high` of one family.
+fn covering(low: u128, high: u128, ipv4: bool) -> IpNet {
+	let width: u32 = if ipv4 { 32 } else { 128 };
+	let len = ((low ^ high).leading_zeros() - (128 - width)).min(width);
+	IpNet::new(bits_to_ip(low, ipv4), len as u8).expect("prefix length is within the address width")
+}
+
+/// Addresses `net` would add when replacing the prefixes around `prefixes[i]`.
+///
+/// `prefixes` is sorted and aggregated, so the prefixes inside `net` form a
+/// contiguous run through `i` and `i + 1`, and none of them is `net` itself.
+fn merge_cost(prefixes: &[IpNet], i: usize, net: &IpNet) -> u128 {
+	let size = |p: &IpNet| 1u128 << (p.max_prefix_len() - p.prefix_len());
+	let left = prefixes[..=i]
+		.iter()
+		.rev()
+		.take_while(|p| net.contains_net(p));
+	let right = prefixes[i + 1..].iter().take_while(|p| net.contains_net(p));
+	let covered: u128 = left.chain(right).map(size).sum();
+	// `net` may span all of IPv6, so count its size minus one.
+	ip_to_bits(net.hostmask()) - (covered - 1)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::net::IpAddr;
+
+	fn nets(list: &[&str]) -> Vec<IpNet> {
+		list.iter().map(|s| s.parse().unwrap()).collect()
+	}
+
+	#[test]
+	fn test_common_supernet() {
+		let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+		assert_eq!(common_supernet::<IpAddr>(&[]), None);
+		assert_eq!(
+			common_supernet(&[ip("192.0.2.1")]),
+			Some(nets(&["192.0.2.1/32"])[0])
+		);
+		assert_eq!(
+			common_supernet(&[ip("0.0.0.0"), ip("255.255.255.255")]),
+			Some(nets(&["0.0.0.0/0"])[0])
+		);
+		assert_eq!(
+			common_supernet(&[ip("::"), ip("8000::")]),
+			Some(nets(&["::/0"])[0])
+		);
+		assert_eq!(common_supernet(&[ip("10.0.0.1"), ip("::1")]), None);
+		assert_eq!(
+			common_supernet(&nets(&["10.0.0.0/8", "10.200.0.0/16"])),
+			Some(nets(&["10.0.0.0/8"])[0])
+		);
+	}
+
+	#[test]
+	fn test_aggregate() {
+		assert_eq!(
+			aggregate(&nets(&[
+				"2001:db8::/33",
+				"10.0.0.0/24",
+				"2001:db8:8000::/33",
+				"10.0.0.0/24",
+				"10.0.2.0/24",
+				"10.0.1.0/24",
+			])),
+			nets(&["10.0.0.0/23", "10.0.2.0/24", "2001:db8::/32"])
+		);
+		assert_eq!(
+			aggregate(&nets(&["255.255.255.255/32", "255.255.255.254/32"])),
+			nets(&["255.255.255.254/31"])
+		);
+		assert_eq!(aggregate(&nets(&["::/1", "8000::/1"])), nets(&["::/0"]));
+		assert!(aggregate(&[]).is_empty());
+	}
+
+	#[test]
+	fn test_summarize() {
+		let input = nets(&["10.0.0.0/24", "10.0.2.0/24", "10.0.8.0/24"]);
+		let summary = summarize(&input, 3);
+		assert_eq!(summary.prefixes, input);
+		assert_eq!(summary.added_addresses, 0);
+		assert!(summary.added_classes.is_empty());
+
+		let summary = summarize(&input, 2);
+		assert_eq!(summary.prefixes, nets(&["10.0.0.0/22", "10.0.8.0/24"]));
+		assert_eq!(summary.added_addresses, 512);
+		assert_eq!(summary.added_classes, IpClassSet::from(IpClass::Private));
+
+		// Families are never merged together.
+		let summary = summarize(&nets(&["10.0.0.0/24", "::1/128"]), 0);
+		assert_eq!(summary.prefixes.len(), 2);
+		assert_eq!(summary.added_addresses, 0);
+
+		// A summary spanning all of IPv6 still counts its added space.
+		let summary = summarize(&nets(&["::/128", "ffff::/128"]), 1);
+		assert_eq!(summary.prefixes, nets(&["::/0"]));
+		assert_eq!(summary.added_addresses, u128::MAX - 1);
+	}
+
+	#[test]
+	fn test_summarize_public_routes() {
+		let summary = summarize(&nets(&["8.8.8.0/24", "8.8.9.0/24", "8.8.12.0/24"]), 1);
+		assert_eq!(summary.prefixes, nets(&["8.8.8.0/21"]));
+		assert!(summary.added_classes.is_empty());
+		assert_eq!(summary.added_addresses, 5 * 256);
+	}
+}
@@ -0,0 +1,225 @@
+/* src/ula.rs */
+
+use crate::net::IpNet;
+use core::fmt;
+use core::net::{IpAddr, Ipv6Addr};
+
+#[cfg(feature = "ula")]
+use crate::mac::Eui64;
+#[cfg(feature = "ula")]
+use sha1::{Digest, Sha1};
+
+/// Builds the ULA `/48` `fdXX:XXXX:XXXX::/48` for a 40-bit Global ID.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ula::from_global_id;
+/// use netsem::{IpClass, classify_ip};
+///
+/// let net = from_global_id([0x12, 0x34, 0x56, 0x78, 0x9a]);
+/// assert_eq!(net.to_string(), "fd12:3456:789a::/48");
+/// assert_eq!(classify_ip(net.network()), IpClass::Private);
+/// ```
+#[must_use]
+pub fn from_global_id(global_id: [u8; 5]) -> IpNet {
+	let mut octets = [0u8; 16];
+	octets[0] = 0xfd;
+	octets[1..6].copy_from_slice(&global_id);
+	IpNet::new(IpAddr::V6(Ipv6Addr::from(octets)), 48).expect("/48 is a valid IPv6 prefix length")
+}
+
+/// Derives a Global ID as in RFC 4193 section 3.2.2.
+///
+/// The ID is the low 40 bits of the SHA-1 digest of a 64-bit NTP-format
+/// timestamp followed by an EUI-64. The result is deterministic, so record
+/// the inputs if the prefix must be reproducible.
+#[cfg(feature = "ula")]
+#[must_use]
+pub fn global_id(ntp_timestamp: u64, eui64: Eui64) -> [u8; 5] {
+	let mut sha = Sha1::new();
+	sha.update(ntp_timestamp.to_be_bytes());
+	sha.update(eui64.octets());
+	let digest = sha.finalize();
+	digest[15..20]
+		.try_into()
+		.expect("SHA-1 digests are 20 bytes")
+}
+
+/// Generates a ULA `/48` from a timestamp and EUI-64 (RFC 4193 section 3.2.2).
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ula::generate;
+/// use netsem::{IpClass, MacAddr, classify_ip};
+///
+/// let mac: MacAddr = "00:1b:21:3c:4d:5e".parse().unwrap();
+/// let net = generate(0xe6f1_0a2b_8000_0000, mac.to_eui64());
+/// assert_eq!(net.prefix_len(), 48);
+/// assert_eq!(classify_ip(net.network()), IpClass::Private);
+/// assert_eq!(net, generate(0xe6f1_0a2b_8000_0000, mac.to_eui64()));
+/// ```
+#[cfg(feature = "ula")]
+#[must_use]
+pub fn generate(ntp_timestamp: u64, eui64: Eui64) -> IpNet {
+	from_global_id(global_id(ntp_timestamp, eui64))
+}
+
+/// Converts a system time to the 64-bit NTP timestamp format (RFC 5905).
+///
+/// The upper 32 bits count seconds since 1900 (wrapping each NTP era) and the
+/// lower 32 bits hold the fraction of a second. Times before 1970 count from
+/// 1900 as well; times before 1900 wrap into the previous era.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn ntp_timestamp(time: std::time::SystemTime) -> u64 {
+	const UNIX_TO_NTP_NANOS: i128 = 2_208_988_800 * 1_000_000_000;
+	let since_unix = match time.duration_since(std::time::UNIX_EPOCH) {
+		Ok(after) => after.as_nanos() as i128,
+		Err(before) => -(before.duration().as_nanos() as i128),
+	};
+	let nanos = since_unix + UNIX_TO_NTP_NANOS;
+	let seconds = nanos.div_euclid(1_000_000_000).rem_euclid(1 << 32) as u64;
+	let fraction = ((nanos.rem_euclid(1_000_000_000) as u64) << 32) / 1_000_000_000;
+	(seconds << 32) | fraction
+}
+
+/// Why a prefix is not a well-formed, randomly chosen ULA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum UlaIssue {
+	/// The prefix is not inside `fc00::/7`.
+	NotUla,
+	/// The prefix is inside `fc00::/8`, whose assignment RFC 4193 leaves undefined.
+	Reserved,
+	/// The prefix is shorter than `/48`, so it does not pin down one Global ID.
+	TooShort,
+	/// The Global ID looks hand-picked (e.g., `fd00::/48`, `fd12:3456:789a::/48`)
+	/// and is likely to collide when networks are merged.
+	LowEntropy,
+}
+
+impl fmt::Display for UlaIssue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::NotUla => "not a unique local address",
+			Self::Reserved => "inside the undefined fc00::/8 half of the ULA range",
+			Self::TooShort => "shorter than /48",
+			Self::LowEntropy => "Global ID does not look randomly generated",
+		})
+	}
+}
+
+/// Checks that `net` is a ULA prefix with a plausibly random Global ID.
+///
+/// Prefixes longer than `/48` are checked through their enclosing `/48`.
+/// The Global ID is flagged as [`UlaIssue::LowEntropy`] when its ten hex
+/// digits use fewer than four distinct values (`fd00::`, `fd00:1::`,
+/// `fdff:ffff:ffff::`) or count steadily up or down (`fd12:3456:789a::`).
+/// A random ID trips this heuristic with a probability below 1 in 10,000.
+///
+/// # Examples
+///
+/// ```
+/// use netsem::ula::{UlaIssue, check_ula};
+///
+/// assert_eq!(check_ula(&"fd3c:91e2:07af::/48".parse().unwrap()), Ok(()));
+/// assert_eq!(check_ula(&"fd3c:91e2:07af:10::/64".parse().unwrap()), Ok(()));
+/// assert_eq!(check_ula(&"fd00::/48".parse().unwrap()), Err(UlaIssue::LowEntropy));
+/// assert_eq!(check_ula(&"fc3c:91e2:07af::/48".parse().unwrap()), Err(UlaIssue::Reserved));
+/// assert_eq!(check_ula(&"2001:db8::/48".parse().unwrap()), Err(UlaIssue::NotUla));
+/// ```
+pub fn check_ula(net: &IpNet) -> Result<(), UlaIssue> {
+	let IpAddr::V6(network) = net.network() else {
+		return Err(UlaIssue::NotUla);
+	};
+	let octets = network.octets();
+	if net.prefix_len() < 7 || octets[0] & 0xfe != 0xfc {
+		return Err(UlaIssue::NotUla);
+	}
+	if octets[0] & 0x01 == 0 {
+		return Err(UlaIssue::Reserved);
+	}
+	if net.prefix_len() < 48 {
+		return Err(UlaIssue::TooShort);
+	}
+
+	let nibbles: [u8; 10] = core::array::from_fn(|i| {
+		let octet = octets[1 + i / 2];
+		if i % 2 == 0 { octet >> 4 } else { octet & 0x0f }
+	});
+	let distinct = (0..16u8).filter(|n| nibbles.contains(n)).count();
+	let stepping = |step: u8| {
+		nibbles
+			.windows(2)
+			.all(|pair| pair[1] == pair[0].wrapping_add(step) & 0x0f)
+	};
+	if distinct < 4 || stepping(1) || stepping(0x0f) {
+		return Err(UlaIssue::LowEntropy);
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn net(s: &str) -> IpNet {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn test_check_ula() {
+		for bad in [
+			"fd00::/48",
+			"fd00:1::/48",
+			"fdff:ffff:ffff::/48",
+			"fd12:3456:789a::/48",
+			"fdfe:dcba:9876::/48",
+			"fdab:abab:abab::/48",
+		] {
+			assert_eq!(check_ula(&net(bad)), Err(UlaIssue::LowEntropy), "{bad}");
+		}
+		assert_eq!(check_ula(&net("fd00::/8")), Err(UlaIssue::TooShort));
+		assert_eq!(check_ula(&net("fc00::/7")), Err(UlaIssue::Reserved));
+		assert_eq!(check_ula(&net("10.0.0.0/8")), Err(UlaIssue::NotUla));
+		assert_eq!(check_ula(&net("fe80::/10")), Err(UlaIssue::NotUla));
+		assert_eq!(check_ula(&net("fd5b:e0c2:9d41::/48")), Ok(()));
+	}
+
+	#[test]
+	#[cfg(feature = "ula")]
+	fn test_generate() {
+		let eui = Eui64::new([0x02, 0x1b, 0x21, 0xff, 0xfe, 0x3c, 0x4d, 0x5e]);
+		let a = generate(1, eui);
+		let b = generate(2, eui);
+		assert_ne!(a, b);
+		assert_eq!(a, generate(1, eui));
+		for ula in [a, b] {
+			assert_eq!(crate::classify_ip(ula.network()), crate::IpClass::Private);
+			assert_eq!(check_ula(&ula), Ok(()));
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "std")]
+	fn test_ntp_timestamp() {
+		use std::time::{Duration, UNIX_EPOCH};
+		assert_eq!(ntp_timestamp(UNIX_EPOCH), 2_208_988_800 << 32);
+		let half = UNIX_EPOCH + Duration::from_millis(500);
+		assert_eq!(ntp_timestamp(half), (2_208_988_800 << 32) | 0x8000_0000);
+
+		// Before 1970 the count still starts in 1900.
+		let before = UNIX_EPOCH - Duration::from_secs(10);
+		assert_eq!(ntp_timestamp(before), 2_208_988_790 << 32);
+		let before = UNIX_EPOCH - Duration::from_millis(500);
+		assert_eq!(ntp_timestamp(before), (2_208_988_799 << 32) | 0x8000_0000);
+		let ntp_epoch = UNIX_EPOCH - Duration::from_secs(2_208_988_800);
+		assert_eq!(ntp_timestamp(ntp_epoch), 0);
+		assert_eq!(
+			ntp_timestamp(ntp_epoch - Duration::from_secs(1)),
+			0xffff_ffff << 32
+		);
+	}
+}